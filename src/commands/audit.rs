@@ -0,0 +1,89 @@
+use serenity::framework::standard::{Args, CommandResult};
+use serenity::framework::standard::macros::command;
+use serenity::model::channel::Message;
+use serenity::model::id::ChannelId;
+use serenity::model::user::User;
+use serenity::prelude::*;
+use serenity::utils::{MessageBuilder, parse_channel};
+use tracing::error;
+
+use crate::commands::permissions::MANAGEGUILD_CHECK;
+use crate::{get_bot_datas, get_mut_bot_datas};
+use crate::utils::{GuildsConfig, I18nMessageStore};
+
+#[command]
+#[checks(ManageGuild)]
+async fn auditlog(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let target = args.single::<String>()?;
+    let guild = match msg.guild_id {
+        Some(guild) => guild.0,
+        None => {
+            msg.channel_id.say(&ctx.http, "Can't configure outside a server").await.ok();
+            return Ok(());
+        }
+    };
+    let audit_channel = if target.eq_ignore_ascii_case("off") {
+        Ok(None)
+    } else {
+        parse_channel(&target).or_else(|| target.parse().ok())
+            .map(Some)
+            .ok_or_else(|| "Expected a channel mention or off".to_string())
+    };
+    let response = match audit_channel {
+        Ok(audit_channel) => {
+            let data_lock = get_mut_bot_datas(ctx).await;
+            let mut write_data = data_lock.write().await;
+            let config: &mut GuildsConfig = &mut write_data.guilds_config;
+            match config.set_audit_channel(guild, audit_channel) {
+                Ok(()) => Ok(match audit_channel {
+                    Some(channel) => MessageBuilder::new()
+                        .push("Changes of the settings will now be reported in ")
+                        .channel(ChannelId(channel))
+                        .build(),
+                    None => "Changes of the settings won't be reported anymore".to_string(),
+                }),
+                Err(why) => Err(format!("Could not save the audit log channel: {}", why)),
+            }
+        }
+        Err(why) => Err(why),
+    };
+    let changed = response.is_ok();
+    let response = response.unwrap_or_else(|why| why);
+    if let Err(why) = msg.channel_id.say(&ctx.http, &response).await {
+        error!(error = ?why, "Error sending message");
+    }
+    if changed {
+        log_change(ctx, guild, msg.channel_id.0, &msg.author, &msg.content, &response).await;
+    }
+    Ok(())
+}
+
+/// Reports a change of the settings to the audit log channel of the server, when it has one.
+pub async fn log_change(ctx: &Context, guild: u64, channel: u64, author: &User, command: &str, response: &str) {
+    let (audit_channel, report) = {
+        let datas_lock = get_bot_datas(ctx).await;
+        let read_data = datas_lock.read().await;
+        let audit_channel = match read_data.guilds_config.get_audit_channel(guild) {
+            Some(audit_channel) => audit_channel,
+            None => return,
+        };
+        // the log is read by the whole staff, so it follows the server language only
+        let (lang, _) = read_data.guilds_config.get_guild_config(guild);
+        let i18n_messages: &I18nMessageStore = read_data.i18n_messages.lng(lang).unwrap();
+        let mut report = MessageBuilder::new();
+        report
+            .push_bold_safe(author.tag())
+            .push(format!(" {} ", i18n_messages.audit_change()))
+            .channel(ChannelId(channel))
+            .push_line("")
+            .push_mono_line_safe(command)
+            .push_quote_line_safe(response);
+        (audit_channel, report.build())
+    };
+    // the report quotes the reply, which may mention roles that shouldn't be pinged again
+    if let Err(why) = ChannelId(audit_channel).send_message(&ctx.http, |m| m
+        .content(report)
+        .allowed_mentions(|mentions| mentions.empty_parse())).await {
+        error!(guild, error = ?why, "Error sending to the audit log");
+    }
+}
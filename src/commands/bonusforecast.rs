@@ -0,0 +1,58 @@
+use serenity::framework::standard::{Args, CommandResult};
+use serenity::framework::standard::macros::command;
+use serenity::model::channel::Message;
+use serenity::prelude::*;
+
+use crate::commands::forecast::{parse_count, send_count_error, send_forecast};
+use crate::constants::{BONUS_EVENT_PVE, BONUS_EVENT_PVP, FORECAST_DEFAULT_WEEKS, FORECAST_MAX_WEEKS};
+use crate::get_bot_datas;
+use crate::utils::{BonusEventStore, I18nMessageStore, SpecialEventStore};
+use crate::utils::reset::ResetCycle;
+use crate::utils::time::format_datetime;
+
+#[command]
+async fn bonusforecast(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let guild = msg.channel_id.to_channel(&ctx).await?.guild()
+        .map(|channel| channel.guild_id.0).unwrap_or(0);
+    let weeks = match parse_count(&mut args, FORECAST_DEFAULT_WEEKS, FORECAST_MAX_WEEKS) {
+        Some(weeks) => weeks,
+        None => {
+            send_count_error(ctx, msg, guild, I18nMessageStore::forecast_weeks, FORECAST_MAX_WEEKS).await;
+            return Ok(());
+        }
+    };
+    let (headline, page_label, entries) = {
+        let datas_lock = get_bot_datas(ctx).await;
+        let read_data = &datas_lock.read().await;
+        let lang = read_data.language(guild, msg.channel_id.0, msg.author.id.0);
+        let tz = read_data.timezone(guild, msg.author.id.0);
+        let i18n_messages: &I18nMessageStore = read_data.i18n_messages.lng(lang).unwrap();
+        let bonus_pve: &BonusEventStore = read_data.bonus_pve.lng(lang).unwrap();
+        let bonus_pvp: &BonusEventStore = read_data.bonus_pvp.lng(lang).unwrap();
+        let (calendar, event_store) = &read_data.event;
+        let localized_events: &SpecialEventStore = event_store.lng(lang).unwrap();
+        let first_week = ResetCycle::Weekly.previous(read_data.clock.now());
+        let entries = (0..weeks)
+            .map(|week| first_week + ResetCycle::Weekly.period() * week as i32)
+            .map(|reset| {
+                let week_events = calendar.periods_between(&reset, &(reset + ResetCycle::Weekly.period()));
+                let pve = read_data.event_bonus.effective_pve(BONUS_EVENT_PVE.index_at(reset), &week_events);
+                let pvp = read_data.event_bonus.effective_pvp(BONUS_EVENT_PVP.index_at(reset), &week_events);
+                let lines = pve.iter().map(|bonus| (bonus_pve, i18n_messages.bonus_pve(), bonus))
+                    .chain(pvp.iter().map(|bonus| (bonus_pvp, i18n_messages.bonus_pvp(), bonus)))
+                    .filter_map(|(store, label, (id, event))| {
+                        let bonus = store.get_from_id(*id)?;
+                        match event.and_then(|event| localized_events.get_from_id(event)) {
+                            Some(event) => Some(format!("{} **{}** ({})", i18n_messages.bonus_event(), bonus.name, event.name)),
+                            None => Some(format!("{} **{}**", label, bonus.name)),
+                        }
+                    })
+                    .collect::<Vec<_>>().join("\n");
+                (format_datetime(&reset, &tz), lines)
+            })
+            .collect();
+        (i18n_messages.forecast_bonus_headline().to_string(), i18n_messages.forecast_page().to_string(), entries)
+    };
+
+    send_forecast(ctx, msg, &headline, &page_label, entries).await
+}
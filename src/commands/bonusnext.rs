@@ -1,40 +1,55 @@
-use chrono::{Date, TimeZone, Utc};
-use serenity::framework::standard::CommandResult;
+use serenity::framework::standard::{Args, CommandResult};
 use serenity::framework::standard::macros::command;
 use serenity::model::channel::Message;
 use serenity::prelude::*;
 use serenity::utils::MessageBuilder;
 
 use crate::constants::{
-    BONUS_EVENT_PVE_SIZE_CYCLE,
-    BONUS_EVENT_PVP_SIZE_CYCLE,
-    BONUS_EVENT_START,
+    BONUS_EVENT_PVE,
+    BONUS_EVENT_PVP,
 };
-use crate::get_bot_datas;
-use crate::utils::{BonusEventStore, I18nMessageStore};
-use crate::utils::time::{get_next_week, get_time_left, get_weekly_start};
-
-fn get_bonus_cycle_start() -> Date<Utc> {
-    let zq = BONUS_EVENT_START;
-    Utc.ymd(zq.0, zq.1, zq.2)
-}
+use crate::{BotData, get_bot_datas};
+use crate::commands::bonus::push_event_bonuses;
+use crate::commands::forecast::occurrence_reply;
+use crate::commands::reply::Reply;
+use crate::utils::{BonusEventStore, I18nMessageStore, SpecialEventStore};
+use crate::utils::reset::ResetCycle;
 
 #[command]
-async fn bonusnext(ctx: &Context, msg: &Message) -> CommandResult {
+async fn bonusnext(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    let bonus = args.rest().trim();
     let guild = msg.channel_id.to_channel(&ctx).await?.guild()
         .and_then(|channel| Some(channel.guild_id.0)).unwrap_or(0);
-    let datas_lock = get_bot_datas(ctx).await;
-    let read_data = &datas_lock.read().await;
-    let (lang, _) = read_data.guilds_config.get_guild_config(guild);
+    let reply = {
+        let datas_lock = get_bot_datas(ctx).await;
+        let read_data = &datas_lock.read().await;
+        if bonus.is_empty() {
+            bonusnext_reply(read_data, guild, msg.channel_id.0, msg.author.id.0)
+        } else {
+            bonus_lookup_reply(read_data, guild, msg.channel_id.0, msg.author.id.0, bonus)
+        }
+    };
+    reply.send(ctx, msg.channel_id).await;
+
+    Ok(())
+}
+
+pub fn bonusnext_reply(read_data: &BotData, guild: u64, channel: u64, user: u64) -> Reply {
+    let lang = read_data.language(guild, channel, user);
+    let time = read_data.time_formatter(guild, user);
     let i18n_messages: &I18nMessageStore = &read_data.i18n_messages.lng(lang).unwrap();
     let bonus_pve: &BonusEventStore = &read_data.bonus_pve.lng(lang).unwrap();
     let bonus_pvp: &BonusEventStore = &read_data.bonus_pvp.lng(lang).unwrap();
-    let now = Utc::now();
-    let next_week = get_weekly_start(get_next_week(now));
-    let count_weeks = |since| next_week.signed_duration_since(get_weekly_start(since)).num_weeks();
-    let pve_id = count_weeks(get_bonus_cycle_start()) % BONUS_EVENT_PVE_SIZE_CYCLE;
-    let pvp_id = count_weeks(get_bonus_cycle_start()) % BONUS_EVENT_PVP_SIZE_CYCLE;
-    let (days_left, hours_left, mins_left, secs_left) = get_time_left(next_week, now);
+    let (calendar, event_store) = &read_data.event;
+    let localized_events: &SpecialEventStore = event_store.lng(lang).unwrap();
+    let now = read_data.clock.now();
+    let next_week = ResetCycle::Weekly.next(now);
+    let pve_id = BONUS_EVENT_PVE.index_at(next_week);
+    let pvp_id = BONUS_EVENT_PVP.index_at(next_week);
+    let week_start = ResetCycle::Weekly.previous(next_week);
+    let week_events = calendar.periods_between(&week_start, &(week_start + ResetCycle::Weekly.period()));
+    let pve_bonuses = read_data.event_bonus.effective_pve(pve_id, &week_events);
+    let pvp_bonuses = read_data.event_bonus.effective_pvp(pvp_id, &week_events);
     let current_pve = bonus_pve.get_from_id(pve_id).unwrap();
     let current_pvp = bonus_pvp.get_from_id(pvp_id).unwrap();
     let mut response = MessageBuilder::new();
@@ -43,13 +58,32 @@ async fn bonusnext(ctx: &Context, msg: &Message) -> CommandResult {
         .push(format!("{} {} -- ", i18n_messages.bonus_pve(), &current_pve.name))
         .push_bold_line(&current_pve.description)
         .push(format!("{} {} -- ", i18n_messages.bonus_pvp(), &current_pvp.name))
-        .push_bold_line(&current_pvp.description)
+        .push_bold_line(&current_pvp.description);
+    push_event_bonuses(&mut response, i18n_messages, bonus_pve, localized_events, &pve_bonuses);
+    push_event_bonuses(&mut response, i18n_messages, bonus_pvp, localized_events, &pvp_bonuses);
+    response
         .push(i18n_messages.bonus_next_start())
-        .push_bold_line(format!(" {} {}, {:0>2}:{:0>2}:{:0>2}!", days_left, i18n_messages.time_days(), hours_left, mins_left, secs_left));
+        .push_bold(format!(" {}!", time.countdown(&next_week, &now, i18n_messages.time_days())))
+        .push_line(format!(" ({})", time.datetime(&next_week)));
 
-    if let Err(why) = msg.channel_id.say(&ctx.http, &response).await {
-        println!("Error sending message: {:?}", why);
+    Reply::text(response.build())
+}
+
+/// Looks a bonus up in the PvE then the PvP rotation and tells when it comes back.
+pub fn bonus_lookup_reply(read_data: &BotData, guild: u64, channel: u64, user: u64, name: &str) -> Reply {
+    let lang = read_data.language(guild, channel, user);
+    let i18n_messages: &I18nMessageStore = read_data.i18n_messages.lng(lang).unwrap();
+    let rotations = [
+        (i18n_messages.bonus_pve(), BONUS_EVENT_PVE, read_data.bonus_pve.lng(lang).unwrap()),
+        (i18n_messages.bonus_pvp(), BONUS_EVENT_PVP, read_data.bonus_pvp.lng(lang).unwrap()),
+    ];
+    let found = rotations.iter()
+        .find_map(|(label, rotation, store)| {
+            let id = store.find_by_name(name)?;
+            Some((*label, *rotation, id, store.get_from_id(id)?.name.as_str()))
+        });
+    match found {
+        Some((label, rotation, id, bonus)) => occurrence_reply(read_data, guild, channel, user, label, bonus, Some((rotation, id))),
+        None => occurrence_reply(read_data, guild, channel, user, "", name, None),
     }
-    
-    Ok(())
 }
\ No newline at end of file
@@ -0,0 +1,114 @@
+use serenity::framework::standard::{Args, CommandResult};
+use serenity::framework::standard::macros::command;
+use serenity::model::channel::Message;
+use serenity::prelude::*;
+use serenity::utils::MessageBuilder;
+use tracing::error;
+
+use crate::constants::{ZAISHEN_BOUNTY, ZAISHEN_BOUNTY_SIZE_CYCLE};
+use crate::enums::Language;
+use crate::{BotData, get_bot_datas};
+use crate::utils::{I18nMessageStore, ZaishenQuestStore};
+use crate::utils::reset::ResetCycle;
+use crate::utils::time::TimeFormatter;
+
+#[command]
+async fn capture(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    let skill_name = args.rest().trim().to_string();
+    let guild = msg.channel_id.to_channel(&ctx).await?.guild()
+        .map(|channel| channel.guild_id.0).unwrap_or(0);
+    let response = {
+        let datas_lock = get_bot_datas(ctx).await;
+        let read_data = &datas_lock.read().await;
+        let lang = read_data.language(guild, msg.channel_id.0, msg.author.id.0);
+        let time = read_data.time_formatter(guild, msg.author.id.0);
+        capture_reply(read_data, lang, &time, &skill_name)
+    };
+
+    if let Err(why) = msg.channel_id.say(&ctx.http, &response).await {
+        error!(error = ?why, "Error sending message");
+    }
+
+    Ok(())
+}
+
+/// Where and when the elite `skill_name` can be captured from a Zaishen bounty boss.
+fn capture_reply(read_data: &BotData, lang: Language, time: &TimeFormatter, skill_name: &str) -> String {
+    let i18n_messages: &I18nMessageStore = read_data.i18n_messages.lng(lang).unwrap();
+    let zq_bounty: &ZaishenQuestStore = read_data.zaishen_bounty.lng(lang).unwrap();
+    let now = read_data.clock.now();
+    // English names are accepted whatever the server language
+    let skill = read_data.skills.find_by_name(lang, skill_name)
+        .or_else(|| read_data.skills.find_by_name(Language::English, skill_name))
+        .and_then(|id| read_data.skills.lang_and_id(lang, id).map(|skill| (id, skill)));
+
+    let mut response = MessageBuilder::new();
+    match skill {
+        None => {
+            response
+                .push(format!("{} ", i18n_messages.capture_unknown()))
+                .push_bold_line_safe(skill_name);
+        }
+        Some((_, (skill, info))) if !info.map(|info| info.elite).unwrap_or(false) => {
+            response
+                .push_bold(&skill.name)
+                .push_line(format!(" {}", i18n_messages.capture_not_elite()));
+        }
+        Some((id, (skill, _))) => {
+            let bosses = read_data.bounty_bosses.bosses_with_elite(id);
+            if bosses.is_empty() {
+                response
+                    .push(format!("{} ", i18n_messages.capture_no_boss()))
+                    .push_bold_line(&skill.name);
+            } else {
+                response
+                    .push(format!("{} ", i18n_messages.capture_headline()))
+                    .push_bold_line(&skill.name);
+                let today = ZAISHEN_BOUNTY.index_at(now);
+                let mut schedule: Vec<_> = bosses.iter()
+                    .map(|boss| (boss, (boss - today).rem_euclid(ZAISHEN_BOUNTY_SIZE_CYCLE)))
+                    .collect();
+                schedule.sort_by_key(|(_, days)| *days);
+                for (boss, days) in schedule {
+                    let name = zq_bounty.get_from_id(*boss).map(|bounty| bounty.name.as_str()).unwrap_or_default();
+                    response.push(format!("- {} -- ", name));
+                    if days == 0 {
+                        response.push_bold_line(i18n_messages.capture_today());
+                    } else {
+                        let start = ResetCycle::Daily.previous(now) + ResetCycle::Daily.period() * days as i32;
+                        response
+                            .push(format!("{} ", i18n_messages.capture_next()))
+                            .push_bold(time.countdown(&start, &now, i18n_messages.time_days()))
+                            .push_line(format!(" ({})", time.datetime(&start)));
+                    }
+                }
+            }
+        }
+    }
+    response.build()
+}
+
+#[cfg(test)]
+mod test {
+    use chrono_tz::Tz;
+
+    use crate::BotData;
+    use crate::commands::capture::capture_reply;
+    use crate::enums::Language;
+    use crate::utils::database::Database;
+    use crate::utils::time::{TimeFormatter, TimestampStyle};
+
+    #[test]
+    pub fn capture_reply_lists_the_bosses() {
+        let read_data = BotData::init(Database::open_in_memory().unwrap());
+        let time = TimeFormatter::new(Tz::UTC, TimestampStyle::Plain);
+        let reply = capture_reply(&read_data, Language::English, &time, "attuned was songkai");
+        assert!(reply.starts_with("Zaishen bounty targets carrying **Attuned Was Songkai**"));
+        assert!(reply.contains("- Chung, the Attuned -- "));
+        // English names are understood on French servers
+        let reply = capture_reply(&read_data, Language::French, &time, "Attuned Was Songkai");
+        assert!(reply.contains("- Chung l'Accordé -- "));
+        let reply = capture_reply(&read_data, Language::English, &time, "Healing Signet");
+        assert!(reply.contains("is not an elite skill"));
+    }
+}
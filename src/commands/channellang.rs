@@ -0,0 +1,46 @@
+use serenity::framework::standard::{Args, CommandResult};
+use serenity::framework::standard::macros::command;
+use serenity::model::channel::Message;
+use serenity::prelude::*;
+use tracing::error;
+
+use crate::commands::audit::log_change;
+use crate::commands::permissions::CONFIGURE_CHECK;
+use crate::enums::Language;
+use crate::get_mut_bot_datas;
+use crate::utils::ChannelsConfig;
+
+#[command]
+#[checks(Configure)]
+async fn channellang(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let language = args.single::<String>()?;
+    let channel = msg.channel_id.to_channel(&ctx).await?.guild();
+
+    if let Some(channel) = channel {
+        let language = if language.eq_ignore_ascii_case("reset") { Ok(None) } else { language.parse::<Language>().map(Some) };
+        let response = match language {
+            Ok(lng) => {
+                let data_lock = get_mut_bot_datas(ctx).await;
+                let mut write_data = data_lock.write().await;
+                let config: &mut ChannelsConfig = &mut write_data.channels_config;
+                match (config.set_language(channel.id.0, channel.guild_id.0, lng), lng) {
+                    (Ok(()), Some(lng)) => Ok(format!("From then on, I shall speak {:?} in this channel !", lng)),
+                    (Ok(()), None) => Ok("This channel will use the language of the server again".to_string()),
+                    (Err(why), _) => Err(format!("Could not save the language: {}", why)),
+                }
+            }
+            Err(err) => Err(err),
+        };
+        let changed = response.is_ok();
+        let response = response.unwrap_or_else(|why| why);
+        if let Err(why) = msg.channel_id.say(&ctx.http, &response).await {
+            error!(error = ?why, "Error sending message");
+        }
+        if changed {
+            log_change(ctx, channel.guild_id.0, msg.channel_id.0, &msg.author, &msg.content, &response).await;
+        }
+    } else {
+        msg.channel_id.say(&ctx.http, "Can't configure outside a server").await.ok();
+    }
+    Ok(())
+}
@@ -0,0 +1,101 @@
+use serenity::framework::standard::{Args, CommandResult};
+use serenity::framework::standard::macros::command;
+use serenity::model::channel::Message;
+use serenity::model::id::RoleId;
+use serenity::prelude::*;
+use serenity::utils::{MessageBuilder, parse_role};
+use tracing::error;
+
+use crate::commands::audit::log_change;
+use crate::commands::permissions::MANAGEGUILD_CHECK;
+use crate::{get_bot_datas, get_mut_bot_datas};
+use crate::utils::GuildsConfig;
+
+/// Lists the roles allowed to change the settings on top of the members with Manage Server.
+#[command]
+#[checks(ManageGuild)]
+#[sub_commands(configrole_add, configrole_remove)]
+async fn configrole(ctx: &Context, msg: &Message) -> CommandResult {
+    let guild = match msg.guild_id {
+        Some(guild) => guild.0,
+        None => {
+            msg.channel_id.say(&ctx.http, "Can't configure outside a server").await.ok();
+            return Ok(());
+        }
+    };
+    let roles = {
+        let datas_lock = get_bot_datas(ctx).await;
+        let read_data = datas_lock.read().await;
+        read_data.guilds_config.get_config_roles(guild)
+    };
+    let mut response = MessageBuilder::new();
+    if roles.is_empty() {
+        response.push("Only members who can manage the server can change the settings");
+    } else {
+        response.push("Members who can manage the server and these roles can change the settings: ");
+        for (index, role) in roles.iter().enumerate() {
+            if index > 0 {
+                response.push(", ");
+            }
+            response.role(RoleId(*role));
+        }
+    }
+    // the list is shown without pinging the roles
+    if let Err(why) = msg.channel_id.send_message(&ctx.http, |m| m
+        .content(response.build())
+        .allowed_mentions(|mentions| mentions.empty_parse())).await {
+        error!(error = ?why, "Error sending message");
+    }
+    Ok(())
+}
+
+#[command("add")]
+#[checks(ManageGuild)]
+async fn configrole_add(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    change_config_role(ctx, msg, args, true).await
+}
+
+#[command("remove")]
+#[checks(ManageGuild)]
+async fn configrole_remove(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    change_config_role(ctx, msg, args, false).await
+}
+
+async fn change_config_role(ctx: &Context, msg: &Message, mut args: Args, add: bool) -> CommandResult {
+    let role = args.single::<String>()?;
+    let guild = match msg.guild_id {
+        Some(guild) => guild.0,
+        None => {
+            msg.channel_id.say(&ctx.http, "Can't configure outside a server").await.ok();
+            return Ok(());
+        }
+    };
+    let response = match parse_role(&role).or_else(|| role.parse().ok()) {
+        Some(role) => {
+            let data_lock = get_mut_bot_datas(ctx).await;
+            let mut write_data = data_lock.write().await;
+            let config: &mut GuildsConfig = &mut write_data.guilds_config;
+            let changed = if add { config.add_config_role(guild, role) } else { config.remove_config_role(guild, role) };
+            let mut response = MessageBuilder::new();
+            match (changed, add) {
+                (Ok(true), true) => Ok(response.role(RoleId(role)).push(" can now change the settings").build()),
+                (Ok(true), false) => Ok(response.role(RoleId(role)).push(" can't change the settings anymore").build()),
+                (Ok(false), true) => Err(response.role(RoleId(role)).push(" can already change the settings").build()),
+                (Ok(false), false) => Err(response.role(RoleId(role)).push(" wasn't allowed to change the settings").build()),
+                (Err(why), _) => Err(format!("Could not save the role: {}", why)),
+            }
+        }
+        None => Err("Expected a role mention or id".to_string()),
+    };
+    let changed = response.is_ok();
+    let response = response.unwrap_or_else(|why| why);
+    if let Err(why) = msg.channel_id.send_message(&ctx.http, |m| m
+        .content(&response)
+        .allowed_mentions(|mentions| mentions.empty_parse())).await {
+        error!(error = ?why, "Error sending message");
+    }
+    if changed {
+        log_change(ctx, guild, msg.channel_id.0, &msg.author, &msg.content, &response).await;
+    }
+    Ok(())
+}
@@ -0,0 +1,155 @@
+use chrono::{DateTime, Duration, NaiveDateTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use serenity::Error as SerenityError;
+use serenity::framework::standard::{Args, CommandResult};
+use serenity::framework::standard::macros::command;
+use serenity::http::StatusCode;
+use serenity::model::channel::Message;
+use serenity::model::id::ChannelId;
+use serenity::prelude::*;
+use tracing::error;
+
+use crate::{BotData, get_bot_datas, get_mut_bot_datas};
+use crate::constants::{COUNTDOWN_MAX_DAYS, COUNTDOWN_TICK};
+use crate::enums::Language;
+use crate::utils::countdown::LiveCountdown;
+use crate::utils::I18nMessageStore;
+use crate::utils::reset::ResetCycle;
+use crate::utils::time::{TimeFormatter, TimestampStyle};
+
+#[command]
+async fn countdown(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let kind = args.single::<String>().unwrap_or_default();
+    // the label is shown and stored on a single line
+    let custom_label = args.rest().split_whitespace().collect::<Vec<_>>().join(" ");
+    let guild = msg.channel_id.to_channel(&ctx).await?.guild()
+        .map(|channel| channel.guild_id.0).unwrap_or(0);
+    let countdown = {
+        let datas_lock = get_bot_datas(ctx).await;
+        let read_data = &datas_lock.read().await;
+        let lang = read_data.language(guild, msg.channel_id.0, msg.author.id.0);
+        let tz = read_data.timezone(guild, msg.author.id.0);
+        let now = read_data.clock.now();
+        match countdown_target(read_data, &kind, custom_label, lang, &tz, now) {
+            Ok((target, label)) => {
+                let content = render(read_data.i18n_messages.lng(lang).unwrap(), &label, &target, &now);
+                Ok((LiveCountdown::new(msg.channel_id.0, 0, target, lang, label), content))
+            }
+            Err(why) => Err(why),
+        }
+    };
+    let (mut countdown, content) = match countdown {
+        Ok(countdown) => countdown,
+        Err(why) => {
+            msg.channel_id.say(&ctx.http, why).await.ok();
+            return Ok(());
+        }
+    };
+
+    match msg.channel_id.say(&ctx.http, content).await {
+        Ok(message) => {
+            countdown.message = message.id.0;
+            let datas_lock = get_mut_bot_datas(ctx).await;
+            let mut write_data = datas_lock.write().await;
+            if let Err(why) = write_data.countdowns.add(countdown) {
+                error!(error = ?why, "Error saving countdown");
+            }
+        }
+        Err(why) => error!(error = ?why, "Error sending message"),
+    }
+
+    Ok(())
+}
+
+/// `daily`, `weekly`, `event` (end of the running event or start of the next one)
+/// or a custom `YYYY-MM-DDTHH:MM` in the reader's time zone.
+fn countdown_target(read_data: &BotData, kind: &str, custom_label: String, lang: Language, tz: &Tz, now: DateTime<Utc>) -> Result<(DateTime<Utc>, String), String> {
+    let i18n_messages: &I18nMessageStore = read_data.i18n_messages.lng(lang).unwrap();
+    let with_label = |default: String| if custom_label.is_empty() { default } else { custom_label.clone() };
+    let (target, label) = match kind.to_lowercase().as_str() {
+        "daily" => (ResetCycle::Daily.next(now), with_label(i18n_messages.today_daily_reset().to_string())),
+        "weekly" => (ResetCycle::Weekly.next(now), with_label(i18n_messages.today_weekly_reset().to_string())),
+        "event" => {
+            let (calendar, event_store) = &read_data.event;
+            let localized_events = event_store.lng(lang).unwrap();
+            let periods = calendar.periods_around(&now);
+            let (period, target, status) = periods.iter()
+                .find(|period| period.within(&now))
+                .map(|period| (period, period.1.1, i18n_messages.event_end()))
+                .or_else(|| periods.iter()
+                    .find(|period| period.after(&now))
+                    .map(|period| (period, period.1.0, i18n_messages.event_begin())))
+                .ok_or_else(|| i18n_messages.event_no_running().to_string())?;
+            let name = localized_events.get_from_id(period.0).map(|event| event.name.clone()).unwrap_or_default();
+            (target, with_label(format!("{} -- {}", name, status)))
+        }
+        custom => {
+            let local = NaiveDateTime::parse_from_str(custom, "%Y-%m-%dT%H:%M")
+                .map_err(|_| i18n_messages.countdown_target().to_string())?;
+            let target = tz.from_local_datetime(&local).earliest()
+                .ok_or_else(|| format!("{} {} {}", custom, i18n_messages.countdown_skipped(), tz.name()))?
+                .with_timezone(&Utc);
+            (target, with_label(i18n_messages.countdown_custom().to_string()))
+        }
+    };
+    if target <= now || target - now > Duration::days(COUNTDOWN_MAX_DAYS) {
+        return Err(format!("{} {} {}", i18n_messages.countdown_range(), COUNTDOWN_MAX_DAYS, i18n_messages.time_days()));
+    }
+    Ok((target, label))
+}
+
+fn render(i18n_messages: &I18nMessageStore, label: &str, target: &DateTime<Utc>, now: &DateTime<Utc>) -> String {
+    if now >= target {
+        return format!("{} **{}**", label, i18n_messages.countdown_done());
+    }
+    // the message is edited in place, so the remaining time is written out rather than left to Discord
+    let plain = TimeFormatter::new(Tz::UTC, TimestampStyle::Plain);
+    format!("{} **{}**", label, plain.countdown(target, now, i18n_messages.time_days()))
+}
+
+/// Edits every tracked countdown message when it is due, until its target is reached.
+/// Tracked messages are persisted, so a restart or a reconnect picks them up again.
+pub async fn run_countdowns(ctx: Context) {
+    loop {
+        tokio::time::sleep(COUNTDOWN_TICK).await;
+        let datas_lock = get_bot_datas(&ctx).await;
+        {
+            let read_data = datas_lock.read().await;
+            if !read_data.countdowns.has_due(read_data.clock.now()) {
+                continue;
+            }
+        }
+        let (now, due) = {
+            let mut write_data = datas_lock.write().await;
+            let now = write_data.clock.now();
+            (now, write_data.countdowns.take_due(now))
+        };
+        let mut finished = vec![];
+        for countdown in due {
+            let content = {
+                let read_data = datas_lock.read().await;
+                render(read_data.i18n_messages.lng(countdown.language).unwrap(), &countdown.label, &countdown.target, &now)
+            };
+            let edit = ChannelId(countdown.channel)
+                .edit_message(&ctx.http, countdown.message, |m| m.content(content))
+                .await;
+            match edit {
+                // the message or its channel is gone, nothing left to update
+                Err(SerenityError::Http(why)) if matches!(why.status_code(), Some(StatusCode::NOT_FOUND) | Some(StatusCode::FORBIDDEN)) => {
+                    finished.push(countdown.message);
+                }
+                Err(why) => error!(message = countdown.message, error = ?why, "Error editing countdown"),
+                Ok(_) if countdown.target <= now => finished.push(countdown.message),
+                Ok(_) => {}
+            }
+        }
+        if !finished.is_empty() {
+            let mut write_data = datas_lock.write().await;
+            for message in finished {
+                if let Err(why) = write_data.countdowns.remove(message) {
+                    error!(message, error = ?why, "Error removing countdown");
+                }
+            }
+        }
+    }
+}
@@ -4,25 +4,40 @@ use serenity::framework::standard::macros::command;
 use serenity::model::channel::Message;
 use serenity::prelude::*;
 use serenity::utils::MessageBuilder;
+use tracing::debug;
 
-use crate::{get_bot_datas, I18nStore};
-use crate::constants::DATETIME_FORMAT;
+use crate::{BotData, get_bot_datas, I18nStore};
+use crate::commands::reply::Reply;
+use crate::constants::{BONUS_EVENT_PVE, BONUS_EVENT_PVP};
+use crate::enums::Language;
 use crate::utils::{I18nMessageStore, SpecialEventPeriod, SpecialEventStore};
-use crate::utils::time::{DateTimeRange, get_time_left};
+use crate::utils::calendar::SpecialEventCalendar;
+use crate::utils::time::{DateTimeRange, TimeFormatter};
 
-type EventTuple = (Vec<SpecialEventPeriod>, I18nStore<SpecialEventStore>);
+type EventTuple = (SpecialEventCalendar, I18nStore<SpecialEventStore>);
 
 #[command]
 async fn event(ctx: &Context, msg: &Message) -> CommandResult {
     let guild = msg.channel_id.to_channel(&ctx).await?.guild()
         .and_then(|channel| Some(channel.guild_id.0)).unwrap_or(0);
-    let datas_lock = get_bot_datas(ctx).await;
-    let read_data = &datas_lock.read().await;
-    let (lang, _) = read_data.guilds_config.get_guild_config(guild);
+    let reply = {
+        let datas_lock = get_bot_datas(ctx).await;
+        let read_data = &datas_lock.read().await;
+        event_reply(read_data, guild, msg.channel_id.0, msg.author.id.0)
+    };
+    reply.send(ctx, msg.channel_id).await;
+
+    Ok(())
+}
+
+pub fn event_reply(read_data: &BotData, guild: u64, channel: u64, user: u64) -> Reply {
+    let lang = read_data.language(guild, channel, user);
+    let time = read_data.time_formatter(guild, user);
     let i18n_messages: &I18nStore<I18nMessageStore> = &read_data.i18n_messages;
     let tuple: &EventTuple = &read_data.event;
-    let (periods, event_store) = tuple;
-    let now = Utc::now();
+    let (calendar, event_store) = tuple;
+    let now = read_data.clock.now();
+    let periods = calendar.periods_around(&now);
     let events_left: Vec<_> = periods.iter()
         .filter(|it| it.within(&now) || it.after(&now))
         .collect();
@@ -33,45 +48,61 @@ async fn event(ctx: &Context, msg: &Message) -> CommandResult {
         .filter(|it| it.after(&now))
         .collect();
     let next_event = next_events.first();
-    println!("{:?} {:?}", events_left, running_events);
+    debug!(?events_left, ?running_events, "Special events around now");
     let localized_events = event_store.lng(lang).unwrap();
     let localized_messages = i18n_messages.lng(lang).unwrap();
     let mut response = MessageBuilder::new();
     if running_events.is_empty() {
         response.push_line(localized_messages.event_no_running());
     } else {
-        running_events.iter().for_each(|evt| print_running_event(&mut response, *evt, localized_events, localized_messages, &now));
+        running_events.iter().for_each(|evt| print_running_event(&mut response, evt, localized_events, localized_messages, &now, &time));
+        let running: Vec<SpecialEventPeriod> = running_events.iter().map(|evt| (**evt).clone()).collect();
+        print_event_bonuses(&mut response, read_data, lang, &running, &now);
 
         if let Some(event_period) = next_event {
-            print_next_event(&mut response, event_period, localized_events, localized_messages, &now);
+            print_next_event(&mut response, event_period, localized_events, localized_messages, &now, &time);
         }
     }
-    if let Err(why) = msg.channel_id.say(&ctx.http, &response).await {
-        println!("Error sending message: {:?}", why);
-    }
-
-    Ok(())
+    Reply::text(response.build())
 }
 
-fn print_running_event(response: &mut MessageBuilder, event_period: &SpecialEventPeriod, localized_events: &SpecialEventStore, localized_messages: &I18nMessageStore, now: &DateTime<Utc>) {
+fn print_running_event(response: &mut MessageBuilder, event_period: &SpecialEventPeriod, localized_events: &SpecialEventStore, localized_messages: &I18nMessageStore, now: &DateTime<Utc>, time: &TimeFormatter) {
     let event = localized_events.get_from_id(event_period.0).unwrap();
     let event_range: &DateTimeRange<_> = &event_period.1;
     response.push_bold(&event.name);
     response.push_line(format!(" {} {}", localized_messages.event_started(), &event.note));
     response.push(localized_messages.event_end());
-    let (days_left, hours_left, mins_left, secs_left) = get_time_left(event_range.1, *now);
-    response.push_bold(format!(" {} {}, {:0>2}:{:0>2}:{:0>2}!", days_left, localized_messages.time_days(), hours_left, mins_left, secs_left));
-    response.push_line(format!(" ({})\n", event_range.1.format(DATETIME_FORMAT)));
+    response.push_bold(format!(" {}!", time.countdown(&event_range.1, now, localized_messages.time_days())));
+    response.push_line(format!(" ({})\n", time.datetime(&event_range.1)));
 }
 
-fn print_next_event(response: &mut MessageBuilder, event_period: &SpecialEventPeriod, localized_events: &SpecialEventStore, localized_messages: &I18nMessageStore, now: &DateTime<Utc>) {
+fn print_next_event(response: &mut MessageBuilder, event_period: &SpecialEventPeriod, localized_events: &SpecialEventStore, localized_messages: &I18nMessageStore, now: &DateTime<Utc>, time: &TimeFormatter) {
     let event = localized_events.get_from_id(event_period.0).unwrap();
     let event_range: &DateTimeRange<_> = &event_period.1;
     response.push(localized_messages.event_next());
     response.push_bold(format!(" {}, ", &event.name));
     response.push_line(&event.note);
     response.push(localized_messages.event_begin());
-    let (days_left, hours_left, mins_left, secs_left) = get_time_left(event_range.0, *now);
-    response.push_bold(format!(" {} {}, {:0>2}:{:0>2}:{:0>2}!", days_left, localized_messages.time_days(), hours_left, mins_left, secs_left));
-    response.push_line(format!(" ({})\n", event_range.0.format(DATETIME_FORMAT)));
-}
\ No newline at end of file
+    response.push_bold(format!(" {}!", time.countdown(&event_range.0, now, localized_messages.time_days())));
+    response.push_line(format!(" ({})\n", time.datetime(&event_range.0)));
+}
+
+fn print_event_bonuses(response: &mut MessageBuilder, read_data: &BotData, lang: Language, running: &[SpecialEventPeriod], now: &DateTime<Utc>) {
+    let pve = read_data.event_bonus.effective_pve(BONUS_EVENT_PVE.index_at(*now), running);
+    let pvp = read_data.event_bonus.effective_pvp(BONUS_EVENT_PVP.index_at(*now), running);
+    if !pve.iter().chain(pvp.iter()).any(|(_, event)| event.is_some()) {
+        return;
+    }
+    let localized_messages = read_data.i18n_messages.lng(lang).unwrap();
+    let bonus_pve = read_data.bonus_pve.lng(lang).unwrap();
+    let bonus_pvp = read_data.bonus_pvp.lng(lang).unwrap();
+    response.push_underline_line(localized_messages.event_bonus_headline());
+    let bonuses = pve.iter().filter_map(|(id, _)| bonus_pve.get_from_id(*id)).map(|bonus| (localized_messages.bonus_pve(), bonus))
+        .chain(pvp.iter().filter_map(|(id, _)| bonus_pvp.get_from_id(*id)).map(|bonus| (localized_messages.bonus_pvp(), bonus)));
+    for (label, bonus) in bonuses {
+        response
+            .push(format!("{} {} -- ", label, &bonus.name))
+            .push_bold_line(&bonus.description);
+    }
+    response.push_line("");
+}
@@ -0,0 +1,139 @@
+use serenity::builder::CreateMessage;
+use serenity::framework::standard::{Args, CommandResult};
+use serenity::model::channel::Message;
+use serenity::prelude::*;
+use serenity::utils::MessageBuilder;
+use serenity_utils::menu::{Menu, MenuOptions};
+use tracing::error;
+
+use crate::{BotData, get_bot_datas};
+use crate::commands::reply::Reply;
+use crate::constants::FORECAST_PAGE_SIZE;
+use crate::utils::I18nMessageStore;
+use crate::utils::reset::Rotation;
+
+/// Reads the optional period count of a forecast command, `None` when it isn't a number in `1..=max`.
+pub fn parse_count(args: &mut Args, default: i64, max: i64) -> Option<i64> {
+    if args.is_empty() {
+        return Some(default);
+    }
+    args.single::<i64>().ok().filter(|count| (1..=max).contains(count))
+}
+
+/// Tells the period count isn't in `1..=max`, `message` being the start of the sentence in the reader's language.
+pub async fn send_count_error(ctx: &Context, msg: &Message, guild: u64, message: fn(&I18nMessageStore) -> &str, max: i64) {
+    let response = {
+        let datas_lock = get_bot_datas(ctx).await;
+        let read_data = datas_lock.read().await;
+        let lang = read_data.language(guild, msg.channel_id.0, msg.author.id.0);
+        format!("{} {}", message(read_data.i18n_messages.lng(lang).unwrap()), max)
+    };
+    if let Err(why) = msg.channel_id.say(&ctx.http, response).await {
+        error!(error = ?why, "Error sending message");
+    }
+}
+
+/// Splits the entries in pages of `page_size`, there is always a page even when it's empty.
+pub fn paginate<T>(entries: &[T], page_size: usize) -> Vec<&[T]> {
+    if entries.is_empty() {
+        return vec![entries];
+    }
+    entries.chunks(page_size).collect()
+}
+
+/// Sends one embed field per period, split in pages browsable with the menu controls when they don't fit in one.
+/// Field names are plain dates since Discord doesn't render timestamp markup there.
+pub async fn send_forecast(ctx: &Context, msg: &Message, headline: &str, page_label: &str, entries: Vec<(String, String)>) -> CommandResult {
+    let chunks = paginate(&entries, FORECAST_PAGE_SIZE);
+    let pages: Vec<CreateMessage> = chunks.iter().enumerate()
+        .map(|(number, chunk)| {
+            let mut page = CreateMessage::default();
+            page.embed(|e| {
+                e.title(headline)
+                    .fields(chunk.iter().map(|(name, value)| (name, value, false)))
+                    .footer(|f| f.text(format!("{} {}/{}", page_label, number + 1, chunks.len())))
+            });
+            page
+        })
+        .collect();
+
+    if pages.len() == 1 {
+        let entries = chunks[0];
+        if let Err(why) = msg.channel_id.send_message(&ctx.http, |m| {
+            m.embed(|e| e.title(headline).fields(entries.iter().map(|(name, value)| (name, value, false))))
+        }).await {
+            error!(error = ?why, "Error sending message");
+        }
+        return Ok(());
+    }
+
+    let menu = Menu::new(ctx, msg, &pages, MenuOptions::default());
+    menu.run().await?;
+
+    Ok(())
+}
+
+/// Tells when the entry `index` of a rotation comes back, or until when it lasts if it's the current one.
+/// `None` means the looked up name didn't match any entry.
+pub fn occurrence_reply(read_data: &BotData, guild: u64, channel: u64, user: u64, label: &str, name: &str, entry: Option<(Rotation, i64)>) -> Reply {
+    let lang = read_data.language(guild, channel, user);
+    let time = read_data.time_formatter(guild, user);
+    let i18n_messages: &I18nMessageStore = read_data.i18n_messages.lng(lang).unwrap();
+    let now = read_data.clock.now();
+    let mut response = MessageBuilder::new();
+    let (rotation, index) = match entry {
+        Some(entry) => entry,
+        None => {
+            response
+                .push(format!("{} ", i18n_messages.rotation_unknown()))
+                .push_bold_safe(name);
+            return Reply::text(response.build());
+        }
+    };
+    let start = rotation.occurrence(index, now);
+    let (status, target) = if start <= now {
+        (i18n_messages.rotation_active(), rotation.cycle.next(now))
+    } else {
+        (i18n_messages.rotation_next(), start)
+    };
+    response
+        .push_bold(name)
+        .push_line(format!(" -- {}", label))
+        .push(status)
+        .push_bold(format!(" {}!", time.countdown(&target, &now, i18n_messages.time_days())))
+        .push_line(format!(" ({})", time.datetime(&target)));
+    Reply::text(response.build())
+}
+
+#[cfg(test)]
+mod test {
+    use serenity::framework::standard::{Args, Delimiter};
+
+    use crate::commands::forecast::{paginate, parse_count};
+
+    fn args(message: &str) -> Args {
+        Args::new(message, &[Delimiter::Single(' ')])
+    }
+
+    #[test]
+    pub fn count_within_bounds() {
+        assert_eq!(parse_count(&mut args(""), 7, 63), Some(7));
+        assert_eq!(parse_count(&mut args("1"), 7, 63), Some(1));
+        assert_eq!(parse_count(&mut args("63"), 7, 63), Some(63));
+        assert_eq!(parse_count(&mut args("0"), 7, 63), None);
+        assert_eq!(parse_count(&mut args("64"), 7, 63), None);
+        assert_eq!(parse_count(&mut args("-3"), 7, 63), None);
+        assert_eq!(parse_count(&mut args("week"), 7, 63), None);
+    }
+
+    #[test]
+    pub fn entries_paged() {
+        let entries: Vec<i64> = (0..15).collect();
+        let pages = paginate(&entries, 7);
+        assert_eq!(pages.len(), 3);
+        assert_eq!(pages[0], &entries[0..7]);
+        assert_eq!(pages[2], &[14]);
+        assert_eq!(paginate(&entries[0..7], 7).len(), 1);
+        assert_eq!(paginate::<i64>(&[], 7), vec![&[] as &[i64]]);
+    }
+}
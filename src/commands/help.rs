@@ -0,0 +1,99 @@
+use std::collections::HashSet;
+
+use serenity::builder::CreateEmbed;
+use serenity::framework::standard::{Args, CommandGroup, CommandResult, HelpOptions};
+use serenity::framework::standard::macros::help;
+use serenity::model::channel::Message;
+use serenity::model::id::UserId;
+use serenity::prelude::*;
+
+use crate::{BotData, get_bot_datas};
+use crate::commands::reply::Reply;
+use crate::utils::I18nMessageStore;
+
+/// Lists the commands of every group, or details one command, from the `help-*` keys of the message files.
+#[help]
+async fn help(
+    ctx: &Context,
+    msg: &Message,
+    args: Args,
+    _help_options: &'static HelpOptions,
+    groups: &[&'static CommandGroup],
+    _owners: HashSet<UserId>,
+) -> CommandResult {
+    let guild = msg.channel_id.to_channel(&ctx).await?.guild()
+        .map(|channel| channel.guild_id.0).unwrap_or(0);
+    let command = args.rest().split_whitespace().next().unwrap_or_default().to_lowercase();
+    let reply = {
+        let datas_lock = get_bot_datas(ctx).await;
+        let read_data = &datas_lock.read().await;
+        if command.is_empty() {
+            help_reply(read_data, guild, msg.channel_id.0, msg.author.id.0, groups)
+        } else {
+            command_help_reply(read_data, guild, msg.channel_id.0, msg.author.id.0, groups, &command)
+        }
+    };
+    reply.send(ctx, msg.channel_id).await;
+
+    Ok(())
+}
+
+/// Commands of the group shown in the help, by their main name.
+fn documented(group: &CommandGroup) -> impl Iterator<Item=&'static str> + '_ {
+    group.options.commands.iter()
+        .filter(|command| command.options.help_available)
+        .filter_map(|command| command.options.names.first().copied())
+}
+
+pub fn help_reply(read_data: &BotData, guild: u64, channel: u64, user: u64, groups: &[&'static CommandGroup]) -> Reply {
+    let lang = read_data.language(guild, channel, user);
+    let prefix = read_data.guilds_config.get_prefix(guild);
+    let i18n_messages: &I18nMessageStore = read_data.i18n_messages.lng(lang).unwrap();
+    let embeds = groups.iter()
+        .map(|group| {
+            let lines: Vec<_> = documented(group)
+                .map(|name| format!("`{}{}` -- {}", prefix, name, i18n_messages.help(name, "description").unwrap_or_default()))
+                .collect();
+            let mut embed = CreateEmbed::default();
+            embed
+                .title(i18n_messages.help_group(&group.name.to_lowercase()).unwrap_or(group.name))
+                .description(lines.join("\n"));
+            embed
+        })
+        .collect();
+    Reply {
+        content: format!("__{}__\n{} `{}help <command>`", i18n_messages.help_headline(), i18n_messages.help_details(), prefix),
+        embeds,
+        ..Reply::default()
+    }
+}
+
+pub fn command_help_reply(read_data: &BotData, guild: u64, channel: u64, user: u64, groups: &[&'static CommandGroup], command: &str) -> Reply {
+    let lang = read_data.language(guild, channel, user);
+    let prefix = read_data.guilds_config.get_prefix(guild);
+    let i18n_messages: &I18nMessageStore = read_data.i18n_messages.lng(lang).unwrap();
+    let name = match groups.iter().flat_map(|group| documented(group)).find(|name| *name == command) {
+        Some(name) => name,
+        None => return Reply::text(format!("{} **{}**", i18n_messages.help_unknown(), command)),
+    };
+    let list = |field: &str| -> Vec<String> {
+        i18n_messages.help(name, field)
+            .map(|entries| entries.split('|').map(|entry| format!("`{}`", format!("{}{} {}", prefix, name, entry).trim_end())).collect())
+            .unwrap_or_default()
+    };
+    let mut usage = list("usage");
+    if usage.is_empty() {
+        usage.push(format!("`{}{}`", prefix, name));
+    }
+    let examples = list("example");
+
+    let mut embed = CreateEmbed::default();
+    embed
+        .title(format!("{}{}", prefix, name))
+        .description(i18n_messages.help(name, "description").unwrap_or_default())
+        .field(i18n_messages.help_usage(), usage.join("\n"), false);
+    if !examples.is_empty() {
+        embed.field(i18n_messages.help_examples(), examples.join("\n"), false);
+    }
+    Reply { embeds: vec![embed], ..Reply::default() }
+}
@@ -0,0 +1,222 @@
+use std::borrow::Cow;
+use std::fs;
+use std::process::exit;
+
+use chrono::{DateTime, Utc};
+use serenity::framework::standard::{Args, CommandResult};
+use serenity::framework::standard::macros::command;
+use serenity::model::channel::{AttachmentType, Message};
+use serenity::prelude::*;
+use tracing::error;
+
+use crate::{BotData, get_bot_datas};
+use crate::constants::{
+    BONUS_EVENT_PVE,
+    BONUS_EVENT_PVP,
+    NICHOLAS_TRAVELER,
+    PRE_SEARING,
+    SHINING_BLADE_BOUNTY,
+    VANGUARD_QUEST,
+    ZAISHEN_BOUNTY,
+    ZAISHEN_COMBAT,
+    ZAISHEN_MISSION,
+    ZAISHEN_VANQUISH,
+};
+use crate::enums::Language;
+use crate::utils::ics::{ICS_MAX_DAYS, IcsError, IcsEvent, IcsFeed, IcsRequest, write_calendar};
+use crate::utils::reset::{ResetCycle, Rotation};
+use crate::utils::database::Database;
+use crate::utils::{BonusEventStore, I18nMessageStore, PreSearingGiftStore, ZaishenQuestStore};
+
+#[command]
+async fn ics(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    let guild = msg.channel_id.to_channel(&ctx).await?.guild()
+        .map(|channel| channel.guild_id.0).unwrap_or(0);
+    let export = {
+        let datas_lock = get_bot_datas(ctx).await;
+        let read_data = &datas_lock.read().await;
+        let lang = read_data.language(guild, msg.channel_id.0, msg.author.id.0);
+        let now = read_data.clock.now();
+        match IcsRequest::parse(args.raw(), lang, now) {
+            Ok(request) => {
+                let i18n_messages: &I18nMessageStore = read_data.i18n_messages.lng(request.language).unwrap();
+                Ok((build_calendar(read_data, &request, now), request, i18n_messages.ics_attached().to_string()))
+            }
+            Err(why) => Err(error_message(read_data.i18n_messages.lng(lang).unwrap(), &why)),
+        }
+    };
+    let (calendar, request, attached) = match export {
+        Ok(export) => export,
+        Err(why) => {
+            msg.channel_id.say(&ctx.http, why).await.ok();
+            return Ok(());
+        }
+    };
+    let file = AttachmentType::Bytes {
+        data: Cow::from(calendar.into_bytes()),
+        filename: format!("gw-{}-{}.ics", request.from.format("%Y%m%d"), request.to.format("%Y%m%d")),
+    };
+
+    if let Err(why) = msg.channel_id.send_files(&ctx.http, vec![file], |m| m.content(attached)).await {
+        error!(error = ?why, "Error sending message");
+    }
+
+    Ok(())
+}
+
+fn error_message(i18n_messages: &I18nMessageStore, error: &IcsError) -> String {
+    match error {
+        IcsError::Feed(feed) => format!("{} '{}' (zq, vq, wanted, nick, pre, bonus, event)", i18n_messages.ics_feed(), feed),
+        IcsError::Dates => i18n_messages.ics_dates().to_string(),
+        IcsError::Range => format!("{} {} {}", i18n_messages.ics_range(), ICS_MAX_DAYS, i18n_messages.time_days()),
+    }
+}
+
+/// `gwbot ics <output file> [from] [to] [language] [feeds...]`, writes the calendar without connecting to Discord.
+pub fn run_cli(args: &[String]) {
+    let (path, tokens) = match args.split_first() {
+        Some(split) => split,
+        None => {
+            eprintln!("Usage: gwbot ics <output file> [YYYY-MM-DD] [YYYY-MM-DD] [en|fr] [zq,vq,wanted,nick,pre,bonus,event]");
+            exit(1);
+        }
+    };
+    let bot_data = BotData::init(Database::open_in_memory().expect("Could not create the database"));
+    let now = bot_data.clock.now();
+    let request = IcsRequest::parse(tokens.iter().map(String::as_str), Language::English, now)
+        .unwrap_or_else(|why| {
+            eprintln!("{}", why);
+            exit(1);
+        });
+    let calendar = build_calendar(&bot_data, &request, now);
+    fs::write(path, calendar).unwrap_or_else(|why| panic!("Couldn't write {}: {}", path, why));
+    println!("Calendar written to {}", path);
+}
+
+pub fn build_calendar(read_data: &BotData, request: &IcsRequest, stamp: DateTime<Utc>) -> String {
+    let lang = request.language;
+    let i18n_messages: &I18nMessageStore = read_data.i18n_messages.lng(lang).unwrap();
+    let mut events = vec![];
+    for feed in &request.feeds {
+        match feed {
+            IcsFeed::Zaishen => {
+                let quests: [(&str, &ZaishenQuestStore, Rotation, _); 4] = [
+                    ("mission", read_data.zaishen_mission.lng(lang).unwrap(), ZAISHEN_MISSION, i18n_messages.zaishen_quest_mission()),
+                    ("bounty", read_data.zaishen_bounty.lng(lang).unwrap(), ZAISHEN_BOUNTY, i18n_messages.zaishen_quest_bounty()),
+                    ("combat", read_data.zaishen_combat.lng(lang).unwrap(), ZAISHEN_COMBAT, i18n_messages.zaishen_quest_combat()),
+                    ("vanquish", read_data.zaishen_vanquish.lng(lang).unwrap(), ZAISHEN_VANQUISH, i18n_messages.zaishen_quest_vanquish()),
+                ];
+                for reset in resets(ResetCycle::Daily, request) {
+                    for (kind, store, rotation, label) in &quests {
+                        let quest = store.get_from_id(rotation.index_at(reset)).unwrap();
+                        events.push(IcsEvent {
+                            uid: format!("zq-{}-{}@gwbot", kind, reset.format("%Y%m%d")),
+                            start: reset,
+                            end: reset + ResetCycle::Daily.period(),
+                            summary: format!("{} {}", label, quest.name),
+                            description: String::new(),
+                        });
+                    }
+                }
+            }
+            IcsFeed::Vanguard | IcsFeed::Wanted => {
+                let (kind, store, rotation, label): (&str, &ZaishenQuestStore, Rotation, _) = match feed {
+                    IcsFeed::Vanguard => ("vanguard", read_data.vanguard_quest.lng(lang).unwrap(), VANGUARD_QUEST, i18n_messages.vanguard_quest()),
+                    _ => ("wanted", read_data.shining_blade_bounty.lng(lang).unwrap(), SHINING_BLADE_BOUNTY, i18n_messages.wanted_bounty()),
+                };
+                for reset in resets(ResetCycle::Daily, request) {
+                    let quest = store.get_from_id(rotation.index_at(reset)).unwrap();
+                    events.push(IcsEvent {
+                        uid: format!("{}-{}@gwbot", kind, reset.format("%Y%m%d")),
+                        start: reset,
+                        end: reset + ResetCycle::Daily.period(),
+                        summary: format!("{} {}", label, quest.name),
+                        description: String::new(),
+                    });
+                }
+            }
+            IcsFeed::PreSearing => {
+                let pre_searing: &PreSearingGiftStore = read_data.pre_searing.lng(lang).unwrap();
+                for reset in resets(ResetCycle::Daily, request) {
+                    let gift = pre_searing.get_from_id(PRE_SEARING.index_at(reset)).unwrap();
+                    events.push(IcsEvent {
+                        uid: format!("pre-{}@gwbot", reset.format("%Y%m%d")),
+                        start: reset,
+                        end: reset + ResetCycle::Daily.period(),
+                        summary: format!("{} {} {}", i18n_messages.forecast_pre_searing(), gift.item, i18n_messages.pre_searing_per()),
+                        description: gift.item_url.clone(),
+                    });
+                }
+            }
+            IcsFeed::Nicholas => {
+                let nicholas_gift = read_data.nicholas_traveler.lng(lang).unwrap();
+                for reset in resets(ResetCycle::Weekly, request) {
+                    let gift = nicholas_gift.get_from_id(NICHOLAS_TRAVELER.index_at(reset)).unwrap();
+                    events.push(IcsEvent {
+                        uid: format!("nick-{}@gwbot", reset.format("%Y%m%d")),
+                        start: reset,
+                        end: reset + ResetCycle::Weekly.period(),
+                        summary: format!("{} {} {} {}", i18n_messages.nicholas_gift_collecting(), gift.item, i18n_messages.nicholas_gift_per().trim(), gift.location),
+                        description: format!("{} {} ({})\n{}", i18n_messages.nicholas_gift_in(), gift.region, gift.campaign, gift.item_url),
+                    });
+                }
+            }
+            IcsFeed::Bonus => {
+                let (calendar, _) = &read_data.event;
+                let bonus_pve: &BonusEventStore = read_data.bonus_pve.lng(lang).unwrap();
+                let bonus_pvp: &BonusEventStore = read_data.bonus_pvp.lng(lang).unwrap();
+                for reset in resets(ResetCycle::Weekly, request) {
+                    let week_end = reset + ResetCycle::Weekly.period();
+                    let week_events = calendar.periods_between(&reset, &week_end);
+                    let pve = read_data.event_bonus.effective_pve(BONUS_EVENT_PVE.index_at(reset), &week_events);
+                    let pvp = read_data.event_bonus.effective_pvp(BONUS_EVENT_PVP.index_at(reset), &week_events);
+                    let bonuses = pve.iter().map(|bonus| ("pve", bonus_pve, i18n_messages.bonus_pve(), bonus))
+                        .chain(pvp.iter().map(|bonus| ("pvp", bonus_pvp, i18n_messages.bonus_pvp(), bonus)));
+                    for (kind, store, label, (id, event)) in bonuses {
+                        let bonus = match store.get_from_id(*id) {
+                            Some(bonus) => bonus,
+                            None => continue,
+                        };
+                        let label = if event.is_some() { i18n_messages.bonus_event() } else { label };
+                        events.push(IcsEvent {
+                            uid: format!("bonus-{}-{}-{}@gwbot", kind, id, reset.format("%Y%m%d")),
+                            start: reset,
+                            end: week_end,
+                            summary: format!("{} {}", label, bonus.name),
+                            description: bonus.description.clone(),
+                        });
+                    }
+                }
+            }
+            IcsFeed::Event => {
+                let (calendar, event_store) = &read_data.event;
+                let localized_events = event_store.lng(lang).unwrap();
+                for period in calendar.periods_between(&request.from, &request.to) {
+                    let event = match localized_events.get_from_id(period.0) {
+                        Some(event) => event,
+                        None => continue,
+                    };
+                    events.push(IcsEvent {
+                        uid: format!("event-{}-{}@gwbot", period.0, period.1.0.format("%Y%m%d")),
+                        start: period.1.0,
+                        end: period.1.1,
+                        summary: event.name.clone(),
+                        description: event.note.clone(),
+                    });
+                }
+            }
+        }
+    }
+    write_calendar(&events, stamp)
+}
+
+/// Resets of every period overlapping the requested range.
+fn resets(cycle: ResetCycle, request: &IcsRequest) -> impl Iterator<Item=DateTime<Utc>> {
+    let to = request.to;
+    let mut reset = cycle.previous(request.from);
+    std::iter::from_fn(move || {
+        let current = reset;
+        reset += cycle.period();
+        Some(current).filter(|current| *current < to)
+    })
+}
@@ -2,40 +2,41 @@ use serenity::framework::standard::{Args, CommandResult};
 use serenity::framework::standard::macros::command;
 use serenity::model::channel::Message;
 use serenity::prelude::*;
-use serenity::utils::MessageBuilder;
+use tracing::error;
 
+use crate::commands::audit::log_change;
+use crate::commands::permissions::CONFIGURE_CHECK;
 use crate::enums::Language;
 use crate::get_mut_bot_datas;
 use crate::utils::GuildsConfig;
 
 #[command]
+#[checks(Configure)]
 async fn lang(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
     let language = args.single::<String>()?;
-    let data_lock = get_mut_bot_datas(ctx).await;
-    let mut write_data = data_lock.write().await;
-    let config: &mut GuildsConfig = &mut write_data.guilds_config;
-    let channel = msg.channel_id.to_channel(&ctx).await?.guild();
-
-    if let Some(channel) = channel {
-        let mut response = MessageBuilder::new();
-        Language::from(&language)
-            .and_then(|lng| {
-                config.set_language(channel.guild_id.0, lng);
-                Ok(lng)
-            })
-            .and_then(|lng| {
-                response.push(format!("From then on, I shall speak {:?} on this server !", lng));
-                Ok(lng)
-            })
-            .or_else(|err| {
-                response.push(err);
-                Err(())
-            });
-        if let Err(why) = msg.channel_id.say(&ctx.http, &response).await {
-            println!("Error sending message: {:?}", why);
-        }
-    } else {
-        msg.channel_id.say(&ctx.http, "Can't configure outside a server").await.ok();
+    let guild = msg.channel_id.to_channel(&ctx).await?.guild().map(|channel| channel.guild_id.0);
+    let response = {
+        let data_lock = get_mut_bot_datas(ctx).await;
+        let mut write_data = data_lock.write().await;
+        set_language(&mut write_data.guilds_config, guild, &language)
+    };
+    let changed = response.is_ok();
+    let response = response.unwrap_or_else(|why| why);
+    if let Err(why) = msg.channel_id.say(&ctx.http, &response).await {
+        error!(error = ?why, "Error sending message");
+    }
+    if let (true, Some(guild)) = (changed, guild) {
+        log_change(ctx, guild, msg.channel_id.0, &msg.author, &msg.content, &response).await;
     }
     Ok(())
-}
\ No newline at end of file
+}
+
+/// The reply to send, as an error when nothing changed.
+pub fn set_language(config: &mut GuildsConfig, guild: Option<u64>, language: &str) -> Result<String, String> {
+    let guild = guild.ok_or_else(|| "Can't configure outside a server".to_string())?;
+    let lng = language.parse::<Language>()?;
+    match config.set_language(guild, lng) {
+        Ok(()) => Ok(format!("From then on, I shall speak {:?} on this server !", lng)),
+        Err(why) => Err(format!("Could not save the language: {}", why)),
+    }
+}
@@ -11,10 +11,12 @@ use serenity_utils::{
     menu::{Menu, MenuOptions},
 };
 use serenity_utils::menu::Control;
+use tracing::debug;
 
 use crate::constants::{REACTION_ONE, REACTION_TWO};
 
 #[command]
+#[help_available(false)]
 async fn menu(ctx: &Context, msg: &Message) -> CommandResult {
 
     let channel = msg.channel_id.to_channel(&ctx).await?.guild().unwrap();
@@ -74,7 +76,7 @@ async fn first_page(menu: &mut Menu<'_>, reaction: Reaction) {
 async fn second_page(menu: &mut Menu<'_>, reaction: Reaction) {
     // Remove the reaction used to change the menu.
     let _ = &reaction.delete(&menu.ctx.http).await;
-    println!("{:?}", reaction.emoji);
+    debug!(emoji = ?reaction.emoji, "Menu reaction");
 
     // Set page number to total - 1.
     menu.options.page = 1;
@@ -9,4 +9,29 @@ pub mod nick;
 pub mod nicknext;
 pub mod event;
 pub mod lang;
-pub mod utc;
\ No newline at end of file
+pub mod utc;
+pub mod myutc;
+pub mod pre;
+pub mod vanguard;
+pub mod wanted;
+pub mod today;
+pub mod forecast;
+pub mod zqforecast;
+pub mod nickforecast;
+pub mod bonusforecast;
+pub mod ics;
+pub mod timestamps;
+pub mod countdown;
+pub mod capture;
+pub mod nicktracker;
+pub mod reply;
+pub mod slash;
+pub mod permissions;
+pub mod prefix;
+pub mod help;
+pub mod mylang;
+pub mod channellang;
+pub mod audit;
+pub mod configrole;
+pub mod rules;
+pub mod ratelimit;
\ No newline at end of file
@@ -0,0 +1,44 @@
+use serenity::framework::standard::{Args, CommandResult};
+use serenity::framework::standard::macros::command;
+use serenity::model::channel::Message;
+use serenity::prelude::*;
+use serenity::utils::MessageBuilder;
+use tracing::error;
+
+use crate::enums::Language;
+use crate::get_mut_bot_datas;
+use crate::utils::UsersConfig;
+
+#[command]
+async fn mylang(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let language = args.single::<String>()?;
+    let response = {
+        let data_lock = get_mut_bot_datas(ctx).await;
+        let mut write_data = data_lock.write().await;
+        let config: &mut UsersConfig = &mut write_data.users_config;
+        let mut response = MessageBuilder::new();
+        if language.eq_ignore_ascii_case("reset") {
+            match config.set_language(msg.author.id.0, None) {
+                Ok(()) => response.push("I will speak the language of the channel or the server to you again"),
+                Err(why) => response.push(format!("Could not save the language: {}", why)),
+            };
+        } else {
+            match language.parse::<Language>() {
+                Ok(lng) => {
+                    match config.set_language(msg.author.id.0, Some(lng)) {
+                        Ok(()) => response.push(format!("From then on, I shall speak {:?} to you wherever you ask !", lng)),
+                        Err(why) => response.push(format!("Could not save the language: {}", why)),
+                    };
+                }
+                Err(err) => {
+                    response.push(err);
+                }
+            }
+        }
+        response
+    };
+    if let Err(why) = msg.channel_id.say(&ctx.http, &response).await {
+        error!(error = ?why, "Error sending message");
+    }
+    Ok(())
+}
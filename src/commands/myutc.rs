@@ -0,0 +1,44 @@
+use serenity::framework::standard::{Args, CommandResult};
+use serenity::framework::standard::macros::command;
+use serenity::model::channel::Message;
+use serenity::prelude::*;
+use serenity::utils::MessageBuilder;
+use tracing::error;
+
+use crate::get_mut_bot_datas;
+use crate::utils::UsersConfig;
+use crate::utils::time::parse_timezone;
+
+#[command]
+async fn myutc(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let timezone = args.single::<String>()?;
+    let response = {
+        let data_lock = get_mut_bot_datas(ctx).await;
+        let mut write_data = data_lock.write().await;
+        let config: &mut UsersConfig = &mut write_data.users_config;
+        let mut response = MessageBuilder::new();
+        if timezone.eq_ignore_ascii_case("reset") {
+            match config.set_timezone(msg.author.id.0, None) {
+                Ok(()) => response.push("I will use the server time zone for you again"),
+                Err(why) => response.push(format!("Could not save the time zone: {}", why)),
+            };
+        } else {
+            match parse_timezone(&timezone) {
+                Ok(tz) => {
+                    match config.set_timezone(msg.author.id.0, Some(tz)) {
+                        Ok(()) => response.push(format!("I will now show you times in {}", tz.name())),
+                        Err(why) => response.push(format!("Could not save the time zone: {}", why)),
+                    };
+                }
+                Err(err) => {
+                    response.push(err);
+                }
+            }
+        }
+        response
+    };
+    if let Err(why) = msg.channel_id.say(&ctx.http, &response).await {
+        error!(error = ?why, "Error sending message");
+    }
+    Ok(())
+}
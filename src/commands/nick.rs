@@ -1,62 +1,113 @@
-use chrono::{Date, TimeZone, Utc};
+use serenity::builder::CreateEmbed;
 use serenity::client::Context;
-use serenity::framework::standard::CommandResult;
+use serenity::framework::standard::{Args, CommandResult};
 use serenity::framework::standard::macros::command;
 use serenity::model::channel::Message;
 use serenity::utils::MessageBuilder;
 
-use crate::constants::{EMOTE_MAP, EMOTE_POINT_RIGHT, NICHOLAS_TRAVELER_SIZE_CYCLE, NICHOLAS_TRAVELER_START};
-use crate::get_bot_datas;
+use crate::commands::forecast::send_count_error;
+use crate::commands::nicktracker::{NICK_DONE_COMMAND, NICK_HISTORY_COMMAND, NICK_REMIND_COMMAND};
+use crate::constants::{NICHOLAS_MAX_GIFTS, NICHOLAS_TRAVELER};
+use crate::{BotData, get_bot_datas};
+use crate::commands::reply::Reply;
 use crate::utils::{I18nMessageStore, NicholasGiftData, NicholasGiftStore};
-use crate::utils::time::{get_next_week, get_time_left, get_weekly_start};
-
-fn get_cycle_start() -> Date<Utc> {
-    let zq = NICHOLAS_TRAVELER_START;
-    Utc.ymd(zq.0, zq.1, zq.2)
-}
+use crate::utils::reset::ResetCycle;
 
 #[command]
-async fn nick(ctx: &Context, msg: &Message) -> CommandResult {
+#[sub_commands(nick_done, nick_history, nick_remind)]
+async fn nick(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
     let guild = msg.channel_id.to_channel(&ctx).await?.guild()
-        .and_then(|channel| Some(channel.guild_id.0)).unwrap_or(0);
-    let datas_lock = get_bot_datas(ctx).await;
-    let read_data = &datas_lock.read().await;
-    let (lang, _) = read_data.guilds_config.get_guild_config(guild);
+        .map(|channel| channel.guild_id.0).unwrap_or(0);
+    let gifts = match parse_gifts(&mut args) {
+        Some(gifts) => gifts,
+        None => {
+            send_count_error(ctx, msg, guild, I18nMessageStore::nicholas_gift_count, NICHOLAS_MAX_GIFTS.into()).await;
+            return Ok(());
+        }
+    };
+    let reply = {
+        let datas_lock = get_bot_datas(ctx).await;
+        let read_data = &datas_lock.read().await;
+        nick_reply(read_data, guild, msg.channel_id.0, msg.author.id.0, gifts)
+    };
+    reply.send(ctx, msg.channel_id).await;
+
+    Ok(())
+}
+
+pub fn nick_reply(read_data: &BotData, guild: u64, channel: u64, user: u64, gifts: Option<u32>) -> Reply {
+    let lang = read_data.language(guild, channel, user);
+    let time = read_data.time_formatter(guild, user);
     let i18n_messages: &I18nMessageStore = &read_data.i18n_messages.lng(lang).unwrap();
     let nicholas_gift: &NicholasGiftStore = &read_data.nicholas_traveler.lng(lang).unwrap();
-    let now = Utc::now();
-    let next_week = get_weekly_start(get_next_week(now));
-    let count_weeks = |since| now.signed_duration_since(get_weekly_start(since)).num_weeks();
-    let gift_id = count_weeks(get_cycle_start()) % NICHOLAS_TRAVELER_SIZE_CYCLE;
-    let (days_left, hours_left, mins_left, secs_left) = get_time_left(next_week, now);
+    let now = read_data.clock.now();
+    let next_week = ResetCycle::Weekly.next(now);
+    let gift_id = NICHOLAS_TRAVELER.index_at(now);
 
     let gift = nicholas_gift.get_from_id(gift_id).unwrap();
-    let response = build_response(i18n_messages, days_left, hours_left, mins_left, secs_left, gift);
+    let mut response = MessageBuilder::new();
+    response
+        .push_underline_line(i18n_messages.nicholas_gift_headline())
+        .push(i18n_messages.nicholas_gift_moving())
+        .push_bold(format!(" {}!", time.countdown(&next_week, &now, i18n_messages.time_days())))
+        .push_line(format!(" ({})", time.datetime(&next_week)));
+    Reply { content: response.build(), embeds: vec![gift_embed(i18n_messages, gift, gifts)], ..Reply::default() }
+}
 
-    if let Err(why) = msg.channel_id.send_message(&ctx.http, |m| {
-        m.content(response);
-        m
-    }).await {
-        println!("Error sending message: {:?}", why);
+/// Reads the optional number of presents to compute the items needed for, `None` when it isn't a number in `1..=NICHOLAS_MAX_GIFTS`.
+pub fn parse_gifts(args: &mut Args) -> Option<Option<u32>> {
+    if args.is_empty() {
+        return Some(None);
     }
+    args.single::<u32>().ok()
+        .filter(|gifts| (1..=NICHOLAS_MAX_GIFTS).contains(gifts))
+        .map(Some)
+}
 
-    Ok(())
+/// The gift links to the item on the wiki and shows the map screenshot of its location.
+pub fn gift_embed(i18n_messages: &I18nMessageStore, gift: &NicholasGiftData, gifts: Option<u32>) -> CreateEmbed {
+    let mut embed = CreateEmbed::default();
+    embed
+        .title(&gift.item)
+        .url(&gift.item_url)
+        .description(format!("{} **{}** {} **{}**\n{} **{}** ({}).",
+            i18n_messages.nicholas_gift_collecting(), gift.item, i18n_messages.nicholas_gift_per(), gift.location,
+            i18n_messages.nicholas_gift_in(), gift.region, gift.campaign))
+        .image(gift.map_image_url());
+    if let Some(gifts) = gifts {
+        embed.field(
+            i18n_messages.nicholas_gift_needed(),
+            format!("{} × {} = **{} {}**", gifts, gift.item, gifts * gift.quantity(), gift.item_name()),
+            false,
+        );
+    }
+    if !gift.nearest_outposts.is_empty() {
+        embed.field(i18n_messages.nicholas_gift_outposts(), gift.nearest_outposts.join(", "), false);
+    }
+    embed
 }
 
-fn build_response(i18n_messages: &I18nMessageStore, days_left: i64, hours_left: i64, mins_left: i64, secs_left: i64, gift: &NicholasGiftData) -> MessageBuilder {
-    let mut response = MessageBuilder::new();
-    response
-        .push_underline_line(i18n_messages.nicholas_gift_headline())
-        .push(format!("{} ", i18n_messages.nicholas_gift_collecting()))
-        .push_bold(&gift.item)
-        .push(format!(" {} ", i18n_messages.nicholas_gift_per()))
-        .push_bold_line(&gift.location)
-        .push(format!("{} ", i18n_messages.nicholas_gift_in()))
-        .push_bold(&gift.region)
-        .push_line(format!(" ({}).", &gift.campaign))
-        .push(format!("{} ", i18n_messages.nicholas_gift_moving()))
-        .push_bold_line(format!("{} {}, {:0>2}:{:0>2}:{:0>2}!", days_left, i18n_messages.time_days(), hours_left, mins_left, secs_left))
-        .push(format!("{} {} ", EMOTE_MAP, EMOTE_POINT_RIGHT))
-        .push_spoiler_line(&gift.location_url);
-    response
+#[cfg(test)]
+mod test {
+    use crate::commands::nick::gift_embed;
+    use crate::utils::{I18nMessageStore, NicholasGiftStore};
+
+    #[test]
+    pub fn gift_embed_computes_the_items_needed() {
+        let i18n_messages = I18nMessageStore::from_csv("datas/message_en_US.csv");
+        let gifts = NicholasGiftStore::from_csv("datas/nicolas_traveler_en_US.csv");
+        // 3 Amber Chunks per present in Ferndale
+        let amber = gifts.get_from_id(1).unwrap();
+        let embed = gift_embed(&i18n_messages, amber, Some(5));
+        let fields = embed.0["fields"].as_array().unwrap();
+        assert_eq!(fields[0]["name"], "Items needed");
+        assert_eq!(fields[0]["value"], "5 × 3 Amber Chunks = **15 Amber Chunks**");
+        assert_eq!(fields[1]["name"], "Nearest outposts");
+        assert_eq!(embed.0["image"]["url"], "https://wiki.guildwars.com/wiki/Special:FilePath/Nicholas_the_Traveler_Ferndale_map.jpg");
+
+        let embed = gift_embed(&i18n_messages, amber, None);
+        let fields = embed.0["fields"].as_array().unwrap();
+        assert_eq!(fields.len(), 1);
+        assert_eq!(fields[0]["value"], "House zu Heltzer");
+    }
 }
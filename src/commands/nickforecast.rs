@@ -0,0 +1,45 @@
+use serenity::framework::standard::{Args, CommandResult};
+use serenity::framework::standard::macros::command;
+use serenity::model::channel::Message;
+use serenity::prelude::*;
+
+use crate::commands::forecast::{parse_count, send_count_error, send_forecast};
+use crate::constants::{FORECAST_DEFAULT_WEEKS, NICHOLAS_TRAVELER, NICHOLAS_TRAVELER_SIZE_CYCLE};
+use crate::get_bot_datas;
+use crate::utils::{I18nMessageStore, NicholasGiftStore};
+use crate::utils::reset::ResetCycle;
+use crate::utils::time::format_datetime;
+
+#[command]
+async fn nickforecast(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let guild = msg.channel_id.to_channel(&ctx).await?.guild()
+        .map(|channel| channel.guild_id.0).unwrap_or(0);
+    let weeks = match parse_count(&mut args, FORECAST_DEFAULT_WEEKS, NICHOLAS_TRAVELER_SIZE_CYCLE) {
+        Some(weeks) => weeks,
+        None => {
+            send_count_error(ctx, msg, guild, I18nMessageStore::forecast_weeks, NICHOLAS_TRAVELER_SIZE_CYCLE).await;
+            return Ok(());
+        }
+    };
+    let (headline, page_label, entries) = {
+        let datas_lock = get_bot_datas(ctx).await;
+        let read_data = &datas_lock.read().await;
+        let lang = read_data.language(guild, msg.channel_id.0, msg.author.id.0);
+        let tz = read_data.timezone(guild, msg.author.id.0);
+        let i18n_messages: &I18nMessageStore = read_data.i18n_messages.lng(lang).unwrap();
+        let nicholas_gift: &NicholasGiftStore = read_data.nicholas_traveler.lng(lang).unwrap();
+        let first_week = ResetCycle::Weekly.previous(read_data.clock.now());
+        let entries = (0..weeks)
+            .map(|week| first_week + ResetCycle::Weekly.period() * week as i32)
+            .map(|reset| {
+                let gift = nicholas_gift.get_from_id(NICHOLAS_TRAVELER.index_at(reset)).unwrap();
+                let description = format!("**{}** {} **{}**\n{} {} ({})", gift.item, i18n_messages.nicholas_gift_per(), gift.location,
+                                          i18n_messages.nicholas_gift_in(), gift.region, gift.campaign);
+                (format_datetime(&reset, &tz), description)
+            })
+            .collect();
+        (i18n_messages.forecast_nicholas_headline().to_string(), i18n_messages.forecast_page().to_string(), entries)
+    };
+
+    send_forecast(ctx, msg, &headline, &page_label, entries).await
+}
@@ -1,62 +1,75 @@
-use chrono::{Date, TimeZone, Utc};
-use serenity::framework::standard::CommandResult;
+use serenity::framework::standard::{Args, CommandResult};
 use serenity::framework::standard::macros::command;
 use serenity::model::channel::Message;
 use serenity::prelude::*;
 use serenity::utils::MessageBuilder;
 
-use crate::constants::{EMOTE_MAP, EMOTE_POINT_RIGHT, NICHOLAS_TRAVELER_SIZE_CYCLE, NICHOLAS_TRAVELER_START};
-use crate::get_bot_datas;
-use crate::utils::{I18nMessageStore, NicholasGiftData, NicholasGiftStore};
-use crate::utils::time::{get_next_week, get_time_left, get_weekly_start};
-
-fn get_cycle_start() -> Date<Utc> {
-    let zq = NICHOLAS_TRAVELER_START;
-    Utc.ymd(zq.0, zq.1, zq.2)
-}
+use crate::{BotData, get_bot_datas};
+use crate::commands::forecast::{occurrence_reply, send_count_error};
+use crate::commands::nick::{gift_embed, parse_gifts};
+use crate::commands::reply::Reply;
+use crate::constants::{NICHOLAS_MAX_GIFTS, NICHOLAS_TRAVELER};
+use crate::utils::{I18nMessageStore, NicholasGiftStore};
+use crate::utils::reset::ResetCycle;
 
 #[command]
-async fn nicknext(ctx: &Context, msg: &Message) -> CommandResult {
+async fn nicknext(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    // anything else than a number of presents is an item to look up
+    let item = args.rest().trim().to_string();
+    let lookup = !item.is_empty() && item.parse::<u32>().is_err();
     let guild = msg.channel_id.to_channel(&ctx).await?.guild()
-        .and_then(|channel| Some(channel.guild_id.0)).unwrap_or(0);
-    let datas_lock = get_bot_datas(ctx).await;
-    let read_data = &datas_lock.read().await;
-    let (lang, _) = read_data.guilds_config.get_guild_config(guild);
-    let i18n_messages: &I18nMessageStore = &read_data.i18n_messages.lng(lang).unwrap();
-    let nicholas_gift: &NicholasGiftStore = &read_data.nicholas_traveler.lng(lang).unwrap();
-    let now = Utc::now();
-    let next_week = get_weekly_start(get_next_week(now));
-    let count_weeks = |since| next_week.signed_duration_since(get_weekly_start(since)).num_weeks();
-    let gift_id = count_weeks(get_cycle_start()) % NICHOLAS_TRAVELER_SIZE_CYCLE;
-    let (days_left, hours_left, mins_left, secs_left) = get_time_left(next_week, now);
-
-    let gift = nicholas_gift.get_from_id(gift_id).unwrap();
-    let response = build_response(i18n_messages, days_left, hours_left, mins_left, secs_left, gift);
-
-    if let Err(why) = msg.channel_id.send_message(&ctx.http, |m| {
-        m.content(response);
-        m
-    }).await {
-        println!("Error sending message: {:?}", why);
-    }
+        .map(|channel| channel.guild_id.0).unwrap_or(0);
+    let gifts = match parse_gifts(&mut args) {
+        Some(gifts) => gifts,
+        None if lookup => None,
+        None => {
+            send_count_error(ctx, msg, guild, I18nMessageStore::nicholas_gift_count, NICHOLAS_MAX_GIFTS.into()).await;
+            return Ok(());
+        }
+    };
+    let reply = {
+        let datas_lock = get_bot_datas(ctx).await;
+        let read_data = &datas_lock.read().await;
+        if lookup {
+            gift_lookup_reply(read_data, guild, msg.channel_id.0, msg.author.id.0, &item)
+        } else {
+            nicknext_reply(read_data, guild, msg.channel_id.0, msg.author.id.0, gifts)
+        }
+    };
+    reply.send(ctx, msg.channel_id).await;
 
     Ok(())
 }
 
-fn build_response(i18n_messages: &I18nMessageStore, days_left: i64, hours_left: i64, mins_left: i64, secs_left: i64, gift: &NicholasGiftData) -> MessageBuilder {
+pub fn nicknext_reply(read_data: &BotData, guild: u64, channel: u64, user: u64, gifts: Option<u32>) -> Reply {
+    let lang = read_data.language(guild, channel, user);
+    let time = read_data.time_formatter(guild, user);
+    let i18n_messages: &I18nMessageStore = &read_data.i18n_messages.lng(lang).unwrap();
+    let nicholas_gift: &NicholasGiftStore = &read_data.nicholas_traveler.lng(lang).unwrap();
+    let now = read_data.clock.now();
+    let next_week = ResetCycle::Weekly.next(now);
+    let gift_id = NICHOLAS_TRAVELER.index_at(next_week);
+
+    let gift = nicholas_gift.get_from_id(gift_id).unwrap();
     let mut response = MessageBuilder::new();
     response
         .push_underline_line(i18n_messages.nicholas_gift_next_headline())
-        .push(format!("{} ", i18n_messages.nicholas_gift_collecting()))
-        .push_bold(&gift.item)
-        .push(format!(" {} ", i18n_messages.nicholas_gift_per()))
-        .push_bold_line(&gift.location)
-        .push(format!("{} ", i18n_messages.nicholas_gift_in()))
-        .push_bold(&gift.region)
-        .push_line(format!(" ({}).", &gift.campaign))
-        .push(format!("{} ", i18n_messages.nicholas_gift_found()))
-        .push_bold_line(format!("{} {}, {:0>2}:{:0>2}:{:0>2}!", days_left, i18n_messages.time_days(), hours_left, mins_left, secs_left))
-        .push(format!("{} {} ", EMOTE_MAP, EMOTE_POINT_RIGHT))
-        .push_spoiler_line(&gift.location_url);
-    response
+        .push(i18n_messages.nicholas_gift_found())
+        .push_bold(format!(" {}!", time.countdown(&next_week, &now, i18n_messages.time_days())))
+        .push_line(format!(" ({})", time.datetime(&next_week)));
+    Reply { content: response.build(), embeds: vec![gift_embed(i18n_messages, gift, gifts)], ..Reply::default() }
+}
+
+/// Tells when Nicholas collects an item again.
+pub fn gift_lookup_reply(read_data: &BotData, guild: u64, channel: u64, user: u64, item: &str) -> Reply {
+    let lang = read_data.language(guild, channel, user);
+    let i18n_messages: &I18nMessageStore = read_data.i18n_messages.lng(lang).unwrap();
+    let nicholas_gift: &NicholasGiftStore = read_data.nicholas_traveler.lng(lang).unwrap();
+    match nicholas_gift.find_by_name(item).and_then(|id| Some((id, nicholas_gift.get_from_id(id)?))) {
+        Some((id, gift)) => {
+            let label = format!("{} ({})", gift.location, i18n_messages.today_nicholas());
+            occurrence_reply(read_data, guild, channel, user, &label, &gift.item, Some((NICHOLAS_TRAVELER, id)))
+        }
+        None => occurrence_reply(read_data, guild, channel, user, "", item, None),
+    }
 }
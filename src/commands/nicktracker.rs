@@ -0,0 +1,192 @@
+use chrono::Duration;
+use chrono_tz::Tz;
+use serenity::framework::standard::{Args, CommandResult};
+use serenity::framework::standard::macros::command;
+use serenity::model::channel::Message;
+use serenity::model::id::UserId;
+use serenity::prelude::*;
+use serenity::utils::MessageBuilder;
+use tracing::error;
+
+use crate::commands::forecast::{parse_count, send_forecast};
+use crate::constants::{
+    NICHOLAS_HISTORY_DEFAULT_WEEKS,
+    NICHOLAS_REMINDER_HOURS,
+    NICHOLAS_REMINDER_TICK,
+    NICHOLAS_TRAVELER,
+    NICHOLAS_TRAVELER_SIZE_CYCLE,
+};
+use crate::{get_bot_datas, get_mut_bot_datas};
+use crate::utils::{I18nMessageStore, NicholasGiftStore};
+use crate::utils::reset::ResetCycle;
+use crate::utils::time::{format_datetime, TimeFormatter, TimestampStyle};
+
+#[command("done")]
+async fn nick_done(ctx: &Context, msg: &Message) -> CommandResult {
+    let guild = msg.channel_id.to_channel(&ctx).await?.guild()
+        .map(|channel| channel.guild_id.0).unwrap_or(0);
+    let user = msg.author.id.0;
+    let response = {
+        let datas_lock = get_mut_bot_datas(ctx).await;
+        let mut write_data = datas_lock.write().await;
+        let lang = write_data.language(guild, msg.channel_id.0, user);
+        let now = write_data.clock.now();
+        let week = NICHOLAS_TRAVELER.period_at(now);
+        match write_data.nicholas_tracker.mark_done(user, week, lang) {
+            Ok(added) => {
+                let streak = write_data.nicholas_tracker.get(user).map(|record| record.current_streak(week)).unwrap_or_default();
+                let i18n_messages: &I18nMessageStore = write_data.i18n_messages.lng(lang).unwrap();
+                let nicholas_gift: &NicholasGiftStore = write_data.nicholas_traveler.lng(lang).unwrap();
+                let gift = nicholas_gift.get_from_id(NICHOLAS_TRAVELER.index_at(now)).unwrap();
+                MessageBuilder::new()
+                    .push(format!("{} ", if added { i18n_messages.nicholas_done_marked() } else { i18n_messages.nicholas_done_already() }))
+                    .push_bold(&gift.item)
+                    .push_line(format!(" -- {}", gift.location))
+                    .push(format!("{} ", i18n_messages.nicholas_streak_current()))
+                    .push_bold(streak)
+                    .push_line(format!(" {}", i18n_messages.nicholas_weeks()))
+                    .build()
+            }
+            Err(why) => format!("Could not save the week: {}", why),
+        }
+    };
+
+    if let Err(why) = msg.channel_id.say(&ctx.http, &response).await {
+        error!(error = ?why, "Error sending message");
+    }
+
+    Ok(())
+}
+
+#[command("history")]
+async fn nick_history(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let weeks = parse_count(&mut args, NICHOLAS_HISTORY_DEFAULT_WEEKS, NICHOLAS_TRAVELER_SIZE_CYCLE);
+    let guild = msg.channel_id.to_channel(&ctx).await?.guild()
+        .map(|channel| channel.guild_id.0).unwrap_or(0);
+    let (stats, headline, page_label, entries) = {
+        let datas_lock = get_bot_datas(ctx).await;
+        let read_data = &datas_lock.read().await;
+        let lang = read_data.language(guild, msg.channel_id.0, msg.author.id.0);
+        let tz = read_data.timezone(guild, msg.author.id.0);
+        let i18n_messages: &I18nMessageStore = read_data.i18n_messages.lng(lang).unwrap();
+        let weeks = match weeks {
+            Some(weeks) => weeks,
+            None => {
+                msg.channel_id.say(&ctx.http, format!("{} {}", i18n_messages.nicholas_history_weeks(), NICHOLAS_TRAVELER_SIZE_CYCLE)).await.ok();
+                return Ok(());
+            }
+        };
+        let nicholas_gift: &NicholasGiftStore = read_data.nicholas_traveler.lng(lang).unwrap();
+        let record = read_data.nicholas_tracker.get(msg.author.id.0);
+        let now = read_data.clock.now();
+        let this_week = ResetCycle::Weekly.previous(now);
+        let entries = (0..weeks)
+            .map(|week| this_week - ResetCycle::Weekly.period() * week as i32)
+            .map(|reset| {
+                let gift = nicholas_gift.get_from_id(NICHOLAS_TRAVELER.index_at(reset)).unwrap();
+                let done = record.map(|record| record.is_done(NICHOLAS_TRAVELER.period_at(reset))).unwrap_or_default();
+                let status = if done { i18n_messages.nicholas_history_done() } else { i18n_messages.nicholas_history_missed() };
+                (format_datetime(&reset, &tz), format!("**{}** -- {} ({})", status, gift.item, gift.location))
+            })
+            .collect();
+
+        let week = NICHOLAS_TRAVELER.period_at(now);
+        let mut stats = MessageBuilder::new();
+        stats
+            .push(format!("{} ", i18n_messages.nicholas_streak_current()))
+            .push_bold(record.map(|record| record.current_streak(week)).unwrap_or_default())
+            .push_line(format!(" {}", i18n_messages.nicholas_weeks()))
+            .push(format!("{} ", i18n_messages.nicholas_streak_best()))
+            .push_bold(record.map(|record| record.best_streak()).unwrap_or_default())
+            .push_line(format!(" {}", i18n_messages.nicholas_weeks()))
+            .push(format!("{} ", i18n_messages.nicholas_cycle_collected()))
+            .push_bold_line(format!("{}/{}", record.map(|record| record.cycle_collected()).unwrap_or_default(), NICHOLAS_TRAVELER_SIZE_CYCLE));
+        (stats.build(), i18n_messages.nicholas_history_headline().to_string(), i18n_messages.forecast_page().to_string(), entries)
+    };
+
+    if let Err(why) = msg.channel_id.say(&ctx.http, stats).await {
+        error!(error = ?why, "Error sending message");
+    }
+    send_forecast(ctx, msg, &headline, &page_label, entries).await
+}
+
+#[command("remind")]
+async fn nick_remind(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let reminder = match args.single::<String>().unwrap_or_default().to_lowercase().as_str() {
+        "on" => Some(true),
+        "off" => Some(false),
+        _ => None,
+    };
+    let guild = msg.channel_id.to_channel(&ctx).await?.guild()
+        .map(|channel| channel.guild_id.0).unwrap_or(0);
+    let response = {
+        let datas_lock = get_mut_bot_datas(ctx).await;
+        let mut write_data = datas_lock.write().await;
+        let lang = write_data.language(guild, msg.channel_id.0, msg.author.id.0);
+        let saved = match reminder {
+            Some(reminder) => write_data.nicholas_tracker.set_reminder(msg.author.id.0, reminder, lang),
+            None => Ok(()),
+        };
+        let i18n_messages: &I18nMessageStore = write_data.i18n_messages.lng(lang).unwrap();
+        match (saved, reminder) {
+            (Err(why), _) => format!("Could not save the reminder: {}", why),
+            (Ok(()), Some(true)) => i18n_messages.nicholas_reminder_on().to_string(),
+            (Ok(()), Some(false)) => i18n_messages.nicholas_reminder_off().to_string(),
+            (Ok(()), None) => i18n_messages.nicholas_reminder_usage().to_string(),
+        }
+    };
+
+    if let Err(why) = msg.channel_id.say(&ctx.http, response).await {
+        error!(error = ?why, "Error sending message");
+    }
+
+    Ok(())
+}
+
+/// DMs the users who asked for it when Nicholas is about to move on and they haven't marked the week done.
+pub async fn run_nicholas_reminders(ctx: Context) {
+    loop {
+        tokio::time::sleep(NICHOLAS_REMINDER_TICK).await;
+        let datas_lock = get_bot_datas(&ctx).await;
+        let (now, due) = {
+            let mut write_data = datas_lock.write().await;
+            let now = write_data.clock.now();
+            if ResetCycle::Weekly.next(now) - now > Duration::hours(NICHOLAS_REMINDER_HOURS) {
+                continue;
+            }
+            match write_data.nicholas_tracker.take_reminders(NICHOLAS_TRAVELER.period_at(now)) {
+                Ok(due) => (now, due),
+                Err(why) => {
+                    error!(error = ?why, "Error saving Nicholas reminders");
+                    continue;
+                }
+            }
+        };
+        for (user, lang) in due {
+            let content = {
+                let read_data = datas_lock.read().await;
+                let i18n_messages: &I18nMessageStore = read_data.i18n_messages.lng(lang).unwrap();
+                let nicholas_gift: &NicholasGiftStore = read_data.nicholas_traveler.lng(lang).unwrap();
+                let gift = nicholas_gift.get_from_id(NICHOLAS_TRAVELER.index_at(now)).unwrap();
+                let next_week = ResetCycle::Weekly.next(now);
+                // a DM has no server settings, Discord shows the time in the reader's own time zone
+                let time = TimeFormatter::new(Tz::UTC, TimestampStyle::Discord);
+                let mut content = MessageBuilder::new();
+                content
+                    .push(format!("{} ", i18n_messages.nicholas_reminder()))
+                    .push_bold(&gift.item)
+                    .push_line(format!(" -- {}", gift.location))
+                    .push(i18n_messages.nicholas_gift_moving())
+                    .push_bold_line(format!(" {}!", time.countdown(&next_week, &now, i18n_messages.time_days())));
+                content.build()
+            };
+            let sent = match UserId(user).create_dm_channel(&ctx.http).await {
+                Ok(channel) => channel.say(&ctx.http, content).await.map(|_| ()),
+                Err(why) => Err(why),
+            };
+            if let Err(why) = sent {
+                error!(user, error = ?why, "Error sending Nicholas reminder");
+            }
+        }
+    }
+}
@@ -0,0 +1,64 @@
+use serenity::framework::standard::{Args, CommandOptions, Reason};
+use serenity::framework::standard::macros::check;
+use serenity::model::channel::Message;
+use serenity::model::id::{GuildId, RoleId};
+use serenity::model::Permissions;
+use serenity::prelude::*;
+
+use crate::get_bot_datas;
+use crate::utils::GuildsConfig;
+
+/// Permissions and roles of the author of `msg` in its channel, `None` outside a server.
+async fn author_permissions(ctx: &Context, msg: &Message) -> Option<(GuildId, Permissions, Vec<RoleId>)> {
+    let channel = msg.channel_id.to_channel(&ctx).await.ok()?.guild()?;
+    let guild = channel.guild_id.to_partial_guild(&ctx.http).await.ok()?;
+    let member = guild.member(&ctx.http, msg.author.id).await.ok()?;
+    let permissions = guild.user_permissions_in(&channel, &member).ok()?;
+    Some((guild.id, permissions, member.roles))
+}
+
+/// Whether the author of `msg` has Manage Server in the channel, which server owners and administrators always have.
+pub async fn can_manage_guild(ctx: &Context, msg: &Message) -> bool {
+    author_permissions(ctx, msg).await
+        .map(|(_, permissions, _)| permissions.manage_guild())
+        .unwrap_or(false)
+}
+
+/// Whether the author of `msg` may change the settings of the server.
+pub async fn can_configure(ctx: &Context, msg: &Message) -> bool {
+    match author_permissions(ctx, msg).await {
+        Some((guild, permissions, roles)) => {
+            let datas_lock = get_bot_datas(ctx).await;
+            let read_data = datas_lock.read().await;
+            may_configure(&read_data.guilds_config, guild.0, permissions, &roles)
+        }
+        None => false,
+    }
+}
+
+/// Members with Manage Server, or with one of the roles the server allowed, may change its settings.
+pub fn may_configure(config: &GuildsConfig, guild: u64, permissions: Permissions, roles: &[RoleId]) -> bool {
+    let allowed = config.get_config_roles(guild);
+    permissions.manage_guild() || roles.iter().any(|role| allowed.contains(&role.0))
+}
+
+#[check]
+#[name = "Configure"]
+async fn configure_check(ctx: &Context, msg: &Message, _: &mut Args, _: &CommandOptions) -> Result<(), Reason> {
+    // outside a server the commands answer by themselves that there is nothing to configure
+    if msg.guild_id.is_none() || can_configure(ctx, msg).await {
+        Ok(())
+    } else {
+        Err(Reason::User("Lacking Manage Server or a configuration role".to_string()))
+    }
+}
+
+#[check]
+#[name = "ManageGuild"]
+async fn manage_guild_check(ctx: &Context, msg: &Message, _: &mut Args, _: &CommandOptions) -> Result<(), Reason> {
+    if msg.guild_id.is_none() || can_manage_guild(ctx, msg).await {
+        Ok(())
+    } else {
+        Err(Reason::User("Lacking Manage Server".to_string()))
+    }
+}
@@ -0,0 +1,77 @@
+use chrono::NaiveDate;
+use serenity::framework::standard::{Args, CommandResult};
+use serenity::framework::standard::macros::command;
+use serenity::model::channel::Message;
+use serenity::prelude::*;
+use serenity::utils::MessageBuilder;
+use tracing::error;
+
+use crate::constants::PRE_SEARING;
+use crate::get_bot_datas;
+use crate::utils::{I18nMessageStore, PreSearingGiftData, PreSearingGiftStore};
+use crate::utils::reset::ResetCycle;
+
+#[command]
+async fn pre(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let date = if args.is_empty() {
+        Ok(None)
+    } else {
+        args.single::<NaiveDate>().map(Some)
+    };
+    let guild = msg.channel_id.to_channel(&ctx).await?.guild()
+        .map(|channel| channel.guild_id.0).unwrap_or(0);
+    let response = {
+        let datas_lock = get_bot_datas(ctx).await;
+        let read_data = &datas_lock.read().await;
+        let lang = read_data.language(guild, msg.channel_id.0, msg.author.id.0);
+        let time = read_data.time_formatter(guild, msg.author.id.0);
+        let i18n_messages: &I18nMessageStore = read_data.i18n_messages.lng(lang).unwrap();
+        let pre_searing: &PreSearingGiftStore = read_data.pre_searing.lng(lang).unwrap();
+        let now = read_data.clock.now();
+        match date {
+            Ok(Some(date)) => {
+                let reset = ResetCycle::Daily.reset_of(date);
+                let gift = pre_searing.get_from_id(PRE_SEARING.index_at(reset)).unwrap();
+                build_date_response(i18n_messages, gift, &time.datetime(&reset)).build()
+            }
+            Ok(None) => {
+                let tomorrow = ResetCycle::Daily.next(now);
+                let today_gift = pre_searing.get_from_id(PRE_SEARING.index_at(now)).unwrap();
+                let tomorrow_gift = pre_searing.get_from_id(PRE_SEARING.index_at(tomorrow)).unwrap();
+                build_response(i18n_messages, today_gift, tomorrow_gift, &time.countdown(&tomorrow, &now, i18n_messages.time_days()), &time.datetime(&tomorrow)).build()
+            }
+            Err(_) => i18n_messages.pre_searing_date().to_string(),
+        }
+    };
+
+    if let Err(why) = msg.channel_id.say(&ctx.http, &response).await {
+        error!(error = ?why, "Error sending message");
+    }
+
+    Ok(())
+}
+
+fn build_response(i18n_messages: &I18nMessageStore, today: &PreSearingGiftData, tomorrow: &PreSearingGiftData, countdown: &str, date: &str) -> MessageBuilder {
+    let mut response = MessageBuilder::new();
+    response
+        .push_underline_line(i18n_messages.pre_searing_headline())
+        .push(format!("{} ", i18n_messages.pre_searing_today()))
+        .push_bold(&today.item)
+        .push_line(format!(" {}", i18n_messages.pre_searing_per()))
+        .push(format!("{} ", i18n_messages.pre_searing_tomorrow()))
+        .push_bold_line(&tomorrow.item)
+        .push(i18n_messages.pre_searing_reset())
+        .push_bold(format!(" {}!", countdown))
+        .push_line(format!(" ({})", date));
+    response
+}
+
+fn build_date_response(i18n_messages: &I18nMessageStore, gift: &PreSearingGiftData, date: &str) -> MessageBuilder {
+    let mut response = MessageBuilder::new();
+    response
+        .push_underline_line(i18n_messages.pre_searing_headline())
+        .push(format!("{} {} : ", i18n_messages.pre_searing_on(), date))
+        .push_bold(&gift.item)
+        .push_line(format!(" {}", i18n_messages.pre_searing_per()));
+    response
+}
@@ -0,0 +1,48 @@
+use serenity::framework::standard::{Args, CommandResult};
+use serenity::framework::standard::macros::command;
+use serenity::model::channel::Message;
+use serenity::prelude::*;
+use serenity::utils::MessageBuilder;
+use tracing::error;
+
+use crate::commands::audit::log_change;
+use crate::commands::permissions::CONFIGURE_CHECK;
+use crate::constants::PREFIX_MAX_LENGTH;
+use crate::get_mut_bot_datas;
+use crate::utils::GuildsConfig;
+
+#[command]
+#[checks(Configure)]
+async fn prefix(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let prefix = args.single::<String>()?;
+    let channel = msg.channel_id.to_channel(&ctx).await?.guild();
+
+    if let Some(channel) = channel {
+        let response = if prefix.chars().count() > PREFIX_MAX_LENGTH {
+            Err(format!("The prefix can't be longer than {} characters", PREFIX_MAX_LENGTH))
+        } else {
+            let data_lock = get_mut_bot_datas(ctx).await;
+            let mut write_data = data_lock.write().await;
+            let config: &mut GuildsConfig = &mut write_data.guilds_config;
+            match config.set_prefix(channel.guild_id.0, prefix.clone()) {
+                Ok(()) => Ok(MessageBuilder::new()
+                    .push("Commands on this server now start with ")
+                    .push_mono(&prefix)
+                    .push(", mentioning me works too")
+                    .build()),
+                Err(why) => Err(format!("Could not save the prefix: {}", why)),
+            }
+        };
+        let changed = response.is_ok();
+        let response = response.unwrap_or_else(|why| why);
+        if let Err(why) = msg.channel_id.say(&ctx.http, &response).await {
+            error!(error = ?why, "Error sending message");
+        }
+        if changed {
+            log_change(ctx, channel.guild_id.0, msg.channel_id.0, &msg.author, &msg.content, &response).await;
+        }
+    } else {
+        msg.channel_id.say(&ctx.http, "Can't configure outside a server").await.ok();
+    }
+    Ok(())
+}
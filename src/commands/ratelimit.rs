@@ -0,0 +1,35 @@
+use std::time::Instant;
+
+use tracing::warn;
+
+use crate::BotData;
+use crate::commands::rules::find_command;
+use crate::utils::I18nMessageStore;
+
+/// A command refused by its rate limit.
+pub struct RateLimitRefusal {
+    /// The localized "try again in Ns" reply.
+    pub reply: String,
+    /// Only the first refusal of a window is answered, so spamming the command doesn't spam the channel.
+    pub first: bool,
+}
+
+/// Counts a use of the command invoked as `name`, refused once its bucket used up the window.
+pub fn rate_limit_refusal(read_data: &BotData, guild: u64, channel: u64, user: u64, name: &str) -> Result<(), RateLimitRefusal> {
+    let command = find_command(name).map(|(_, command)| command).unwrap_or(name);
+    let limited = match read_data.rate_limiter.hit(command, user, channel, Instant::now()) {
+        Ok(()) => return Ok(()),
+        Err(limited) => limited,
+    };
+    if limited.first {
+        warn!(guild, channel, user, command, retry_after_s = limited.retry_after.as_secs(), "Rate limit exceeded");
+    }
+    let lang = read_data.language(guild, channel, user);
+    let i18n_messages: &I18nMessageStore = read_data.i18n_messages.lng(lang).unwrap();
+    // a partly elapsed second still has to be waited
+    let seconds = limited.retry_after.as_secs() + u64::from(limited.retry_after.subsec_nanos() > 0);
+    Err(RateLimitRefusal {
+        reply: format!("{} {}s", i18n_messages.rate_limited(), seconds),
+        first: limited.first,
+    })
+}
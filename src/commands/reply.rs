@@ -0,0 +1,77 @@
+use std::borrow::Cow;
+
+use serenity::builder::CreateEmbed;
+use serenity::model::application::interaction::application_command::ApplicationCommandInteraction;
+use serenity::model::application::interaction::InteractionResponseType;
+use serenity::model::channel::AttachmentType;
+use serenity::model::id::ChannelId;
+use serenity::prelude::*;
+use tracing::error;
+
+/// Answer of a command, built once and sent either in the channel of a prefix command
+/// or as the response of a slash command.
+#[derive(Debug, Default)]
+pub struct Reply {
+    pub content: String,
+    pub embeds: Vec<CreateEmbed>,
+    /// Raw bytes and file name, referenced by the embeds as `attachment://<file name>`.
+    pub attachment: Option<(Vec<u8>, String)>,
+}
+
+impl Reply {
+    pub fn text<S: ToString>(content: S) -> Self {
+        Self { content: content.to_string(), ..Self::default() }
+    }
+
+    fn file(&self) -> Option<AttachmentType<'_>> {
+        self.attachment.as_ref().map(|(data, filename)| AttachmentType::Bytes {
+            data: Cow::from(data.as_slice()),
+            filename: filename.clone(),
+        })
+    }
+
+    pub async fn send(self, ctx: &Context, channel: ChannelId) {
+        if let Err(why) = channel.send_message(&ctx.http, |m| {
+            if !self.content.is_empty() {
+                m.content(&self.content);
+            }
+            if let Some(file) = self.file() {
+                m.add_file(file);
+            }
+            m.set_embeds(self.embeds.clone())
+        }).await {
+            error!(error = ?why, "Error sending message");
+        }
+    }
+
+    pub async fn respond(self, ctx: &Context, command: &ApplicationCommandInteraction) {
+        if let Err(why) = command.create_interaction_response(&ctx.http, |r| {
+            r.kind(InteractionResponseType::ChannelMessageWithSource)
+                .interaction_response_data(|d| {
+                    if !self.content.is_empty() {
+                        d.content(&self.content);
+                    }
+                    if let Some(file) = self.file() {
+                        d.add_file(file);
+                    }
+                    d.set_embeds(self.embeds.clone())
+                })
+        }).await {
+            error!(command = %command.data.name, error = ?why, "Error responding to slash command");
+        }
+    }
+    /// Answers a slash command whose response was deferred with `defer`.
+    pub async fn follow_up(self, ctx: &Context, command: &ApplicationCommandInteraction) {
+        if let Err(why) = command.create_followup_message(&ctx.http, |f| {
+            if !self.content.is_empty() {
+                f.content(&self.content);
+            }
+            if let Some(file) = self.file() {
+                f.add_file(file);
+            }
+            f.set_embeds(self.embeds.clone())
+        }).await {
+            error!(command = %command.data.name, error = ?why, "Error following up slash command");
+        }
+    }
+}
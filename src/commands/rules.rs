@@ -0,0 +1,209 @@
+use std::collections::BTreeSet;
+
+use serenity::framework::standard::{Args, CommandGroup, CommandResult};
+use serenity::framework::standard::macros::command;
+use serenity::model::channel::Message;
+use serenity::model::id::ChannelId;
+use serenity::prelude::*;
+use serenity::utils::{MessageBuilder, parse_channel};
+use tracing::error;
+
+use crate::{BotData, CONFIGURATION_GROUP, get_bot_datas, get_mut_bot_datas, GROUPS};
+use crate::commands::audit::log_change;
+use crate::commands::permissions::CONFIGURE_CHECK;
+use crate::utils::I18nMessageStore;
+use crate::utils::rules::{CommandRules, RuleRefusal};
+
+/// Rules can target every command at once.
+const ALL_COMMANDS: &str = "all";
+
+/// The group and the main name of the command invoked as `name`, sub-commands count as their parent.
+pub fn find_command(name: &str) -> Option<(&'static CommandGroup, &'static str)> {
+    GROUPS.iter()
+        .flat_map(|group| group.options.commands.iter().map(move |command| (*group, command)))
+        .find(|(_, command)| command.options.names.contains(&name)
+            || command.options.sub_commands.iter().any(|sub_command| sub_command.options.names.contains(&name)))
+        .map(|(group, command)| (group, command.options.names[0]))
+}
+
+/// Rule targets of the command invoked as `name`, from the most specific: the command, its group and `all`.
+/// The configuration commands are never targeted so they can't be locked out.
+pub fn rule_targets(name: &str) -> Option<[String; 3]> {
+    find_command(name)
+        .filter(|(group, _)| group.name != CONFIGURATION_GROUP.name)
+        .map(|(group, command)| [command.to_string(), group.name.to_lowercase(), ALL_COMMANDS.to_string()])
+}
+
+/// The localized reply to a command refused by the rules of the guild, `None` when it's allowed.
+pub fn rule_refusal(read_data: &BotData, guild: u64, channel: u64, user: u64, name: &str) -> Option<String> {
+    let targets = rule_targets(name)?;
+    let targets: Vec<&str> = targets.iter().map(String::as_str).collect();
+    let refusal = read_data.command_rules.check(guild, &targets, channel).err()?;
+    let lang = read_data.language(guild, channel, user);
+    let i18n_messages: &I18nMessageStore = read_data.i18n_messages.lng(lang).unwrap();
+    let mut response = MessageBuilder::new();
+    match refusal {
+        RuleRefusal::Disabled => response.push(i18n_messages.rules_disabled()),
+        RuleRefusal::Channels(channels) => {
+            response.push(format!("{} ", i18n_messages.rules_channels()));
+            for (index, channel) in channels.into_iter().enumerate() {
+                if index > 0 {
+                    response.push(", ");
+                }
+                response.channel(ChannelId(channel));
+            }
+            &mut response
+        }
+    };
+    Some(response.build())
+}
+
+/// Whether `target` names a command, a group or every command that rules may apply to.
+fn is_target(target: &str) -> bool {
+    target == ALL_COMMANDS
+        || rule_targets(target).map(|targets| targets[0] == target).unwrap_or(false)
+        || GROUPS.iter().any(|group| group.name != CONFIGURATION_GROUP.name && group.name.to_lowercase() == target)
+}
+
+/// Lists the commands turned off and the channels commands are limited to.
+#[command]
+#[checks(Configure)]
+#[sub_commands(rules_disable, rules_enable, rules_restrict, rules_unrestrict)]
+async fn rules(ctx: &Context, msg: &Message) -> CommandResult {
+    let guild = match msg.guild_id {
+        Some(guild) => guild.0,
+        None => {
+            msg.channel_id.say(&ctx.http, "Can't configure outside a server").await.ok();
+            return Ok(());
+        }
+    };
+    let response = {
+        let datas_lock = get_bot_datas(ctx).await;
+        let read_data = datas_lock.read().await;
+        let mut response = MessageBuilder::new();
+        match read_data.command_rules.get(guild).filter(|rules| !rules.is_empty()) {
+            None => {
+                response.push("Every command can be used in every channel");
+            }
+            Some(rules) => {
+                if !rules.disabled.is_empty() {
+                    let disabled: Vec<_> = rules.disabled.iter().map(String::as_str).collect();
+                    response.push("Turned off: ").push_bold_line(disabled.join(", "));
+                }
+                for (target, channels) in &rules.channels {
+                    response.push_bold(target).push(" only in ");
+                    for (index, channel) in channels.iter().enumerate() {
+                        if index > 0 {
+                            response.push(", ");
+                        }
+                        response.channel(ChannelId(*channel));
+                    }
+                    response.push_line("");
+                }
+            }
+        }
+        response.build()
+    };
+    if let Err(why) = msg.channel_id.say(&ctx.http, response).await {
+        error!(error = ?why, "Error sending message");
+    }
+    Ok(())
+}
+
+#[command("disable")]
+#[checks(Configure)]
+async fn rules_disable(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let target = args.single::<String>()?.to_lowercase();
+    change_rules(ctx, msg, &target, |rules, guild| {
+        if target == ALL_COMMANDS {
+            return Err("Every command can't be turned off, turn off the groups instead".to_string());
+        }
+        match rules.set_disabled(guild, &target, true) {
+            Ok(true) => Ok(format!("{} is now turned off on this server", target)),
+            Ok(false) => Err(format!("{} already is turned off", target)),
+            Err(why) => Err(format!("Could not save the rule: {}", why)),
+        }
+    }).await
+}
+
+#[command("enable")]
+#[checks(Configure)]
+async fn rules_enable(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let target = args.single::<String>()?.to_lowercase();
+    change_rules(ctx, msg, &target, |rules, guild| {
+        match rules.set_disabled(guild, &target, false) {
+            Ok(true) => Ok(format!("{} is turned on again", target)),
+            Ok(false) => Err(format!("{} isn't turned off", target)),
+            Err(why) => Err(format!("Could not save the rule: {}", why)),
+        }
+    }).await
+}
+
+#[command("restrict")]
+#[checks(Configure)]
+async fn rules_restrict(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let target = args.single::<String>()?.to_lowercase();
+    let channels: Option<BTreeSet<u64>> = args.iter::<String>()
+        .map(|channel| channel.ok().and_then(|channel| parse_channel(&channel).or_else(|| channel.parse().ok())))
+        .collect();
+    change_rules(ctx, msg, &target, |rules, guild| {
+        let channels = channels.filter(|channels| !channels.is_empty())
+            .ok_or_else(|| "Expected one or more channel mentions".to_string())?;
+        let mut response = MessageBuilder::new();
+        response.push(format!("{} can now only be used in ", target));
+        for (index, channel) in channels.iter().enumerate() {
+            if index > 0 {
+                response.push(", ");
+            }
+            response.channel(ChannelId(*channel));
+        }
+        match rules.set_channels(guild, &target, channels) {
+            Ok(()) => Ok(response.build()),
+            Err(why) => Err(format!("Could not save the rule: {}", why)),
+        }
+    }).await
+}
+
+#[command("unrestrict")]
+#[checks(Configure)]
+async fn rules_unrestrict(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let target = args.single::<String>()?.to_lowercase();
+    change_rules(ctx, msg, &target, |rules, guild| {
+        match rules.set_channels(guild, &target, BTreeSet::new()) {
+            Ok(()) => Ok(format!("{} can be used in every channel again", target)),
+            Err(why) => Err(format!("Could not save the rule: {}", why)),
+        }
+    }).await
+}
+
+/// Applies `change` to the rules of the guild once the target is known, then replies and reports the change.
+async fn change_rules(
+    ctx: &Context,
+    msg: &Message,
+    target: &str,
+    change: impl FnOnce(&mut CommandRules, u64) -> Result<String, String>,
+) -> CommandResult {
+    let guild = match msg.guild_id {
+        Some(guild) => guild.0,
+        None => {
+            msg.channel_id.say(&ctx.http, "Can't configure outside a server").await.ok();
+            return Ok(());
+        }
+    };
+    let response = if is_target(target) {
+        let data_lock = get_mut_bot_datas(ctx).await;
+        let mut write_data = data_lock.write().await;
+        change(&mut write_data.command_rules, guild)
+    } else {
+        Err(format!("Expected a command, a group (rotations, planning, tools) or {}, the configuration commands can't be restricted", ALL_COMMANDS))
+    };
+    let changed = response.is_ok();
+    let response = response.unwrap_or_else(|why| why);
+    if let Err(why) = msg.channel_id.say(&ctx.http, &response).await {
+        error!(error = ?why, "Error sending message");
+    }
+    if changed {
+        log_change(ctx, guild, msg.channel_id.0, &msg.author, &msg.content, &response).await;
+    }
+    Ok(())
+}
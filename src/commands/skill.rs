@@ -3,42 +3,78 @@ use std::env;
 
 use image::{DynamicImage, GenericImage, ImageBuffer, ImageResult};
 use image::io::Reader as ImageReader;
+use serenity::builder::CreateEmbed;
 use serenity::framework::standard::{Args, CommandResult};
 use serenity::framework::standard::macros::command;
-use serenity::model::channel::AttachmentType;
 use serenity::model::channel::{EmbedImage, Message};
 use serenity::model::guild::Emoji;
 use serenity::prelude::*;
 use serenity::utils::MessageBuilder;
-use tokio::fs::File;
 
 use crate::enums::Language;
+use crate::commands::reply::Reply;
 use crate::get_bot_datas;
-use crate::utils::{AttributeStore, I18nMessageStore, ProfessionStore, SKillI18nStore};
+use crate::utils::{AttributeStore, I18nMessageStore, ProfessionStore, SKillI18nStore, SkillInfo, SkillName};
 use crate::utils::skill::{SkillCodeParser, SkillCodeRecord};
 
 #[command]
-async fn skill(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
-    let code_skill = args.single::<String>()?;
-    let skill_record = SkillCodeParser::parse(code_skill.clone());
+async fn skill(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    let query = args.rest().trim().to_string();
+    if query.is_empty() {
+        return Err("Expected a build template code or a skill name".into());
+    }
 
     let channel = msg.channel_id.to_channel(&ctx).await?.guild();
     let guild = channel.and_then(|channel| Some(channel.guild_id.0)).unwrap_or(0);
     // println!("guild id: {}", guild);
     // println!("env guild id: {}", env::var("HOME_GUILD").expect("missing env HOME_GUILD"));
+    let reply = skill_reply(ctx, guild, msg.channel_id.0, msg.author.id.0, &query).await?;
+    reply.send(ctx, msg.channel_id).await;
+
+    // msg.reply(ctx, code_skill).await?;
+
+    Ok(())
+}
+
+/// A skill name shows that skill, anything else is decoded as a build template code.
+pub async fn skill_reply(ctx: &Context, guild: u64, channel: u64, user: u64, query: &str) -> serenity::Result<Reply> {
     let datas_lock = get_bot_datas(ctx).await;
     let read_data = &datas_lock.read().await;
-    let (lang, _) = read_data.guilds_config.get_guild_config(guild);
+    let lang = read_data.language(guild, channel, user);
     let i18n_messages: &I18nMessageStore = &read_data.i18n_messages.lng(lang).unwrap();
+    let skills_store: &SKillI18nStore = &read_data.skills;
 
-    let emojis = ctx.http.get_guild(guild).await?.emojis;
+    // English names are accepted whatever the server language
+    let skill = skills_store.find_by_name(lang, query)
+        .or_else(|| skills_store.find_by_name(Language::English, query))
+        .and_then(|id| skills_store.lang_and_id(lang, id));
+    if let Some((skill, info)) = skill {
+        return Ok(Reply { embeds: vec![skill_embed(skill, info)], ..Reply::default() });
+    }
+
+    let code_skill = query.to_string();
+    let skill_record = match SkillCodeParser::parse(code_skill.clone()) {
+        Some(skill_record) => skill_record,
+        None => {
+            let mut response = MessageBuilder::new();
+            response
+                .push(format!("{} ", i18n_messages.skill_invalid_code()))
+                .push_bold_line_safe(query);
+            return Ok(Reply::text(response.build()));
+        }
+    };
+    // profession emojis come from the server, there are none in private messages
+    let emojis = if guild == 0 {
+        HashMap::new()
+    } else {
+        ctx.http.get_guild(guild).await?.emojis
+    };
     let emoji_lookup = emojis.iter()
         .map(|(_id, emoji)| (emoji.name.clone(), emoji.clone()))
         .collect::<HashMap<String, Emoji>>();
 
     let mut response = MessageBuilder::new();
     {
-        let skills_store: &SKillI18nStore = &read_data.skills;
         let attributes_store: &AttributeStore = &read_data.attributes.lng(lang).unwrap();
         let professions_store: &ProfessionStore = &read_data.professions.lng(lang).unwrap();
         set_skill_header_msg(&code_skill, &skill_record, professions_store, &emoji_lookup, &mut response);
@@ -48,28 +84,35 @@ async fn skill(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
     let build_image = get_embedded_build(&skill_record.skills);
     let image_name = format!("{}.jpg", &code_skill);
     let path_build = format!("tmp/{}", &image_name);
-    if let Ok(build_image) = &build_image {
-        build_image.save(&path_build).unwrap();
-    }
+    let saved = match &build_image {
+        Ok(build_image) => build_image.save(&path_build).is_ok(),
+        Err(_) => false,
+    };
 
-    let build_attachment = File::open(&path_build).await;
-    let build_attachment = build_attachment.as_ref();
-    if let Err(why) = msg.channel_id.send_message(&ctx.http, |m| {
-        m.content(response);
-        if build_image.is_ok() {
-            let build_attachment = build_attachment.unwrap();
-            let attachment = AttachmentType::File { file: build_attachment, filename: image_name.clone() };
-            m.embed(|e| e.image(format!("attachment://{}", &image_name)));
-            m.add_file(attachment);
+    let mut reply = Reply::text(response.build());
+    if saved {
+        if let Ok(data) = tokio::fs::read(&path_build).await {
+            let mut embed = CreateEmbed::default();
+            embed.image(format!("attachment://{}", &image_name));
+            reply.embeds.push(embed);
+            reply.attachment = Some((data, image_name));
         }
-        m
-    }).await {
-        println!("Error sending message: {:?}", why);
     }
+    Ok(reply)
+}
 
-    // msg.reply(ctx, code_skill).await?;
-
-    Ok(())
+fn skill_embed(skill: &SkillName, info: Option<&SkillInfo>) -> CreateEmbed {
+    let mut embed = CreateEmbed::default();
+    embed
+        .title(&skill.name)
+        .description(&skill.description);
+    if let Some(info) = info {
+        if !info.skill_uri.is_empty() {
+            embed.url(&info.skill_uri);
+        }
+        embed.thumbnail(&info.skill_icon);
+    }
+    embed
 }
 
 fn add_skill_set_msg(skill_record: &SkillCodeRecord, lang: Language, i18n_messages: &I18nMessageStore, skills_store: &SKillI18nStore, response: &mut MessageBuilder) {
@@ -81,12 +124,17 @@ fn add_skill_set_msg(skill_record: &SkillCodeRecord, lang: Language, i18n_messag
 }
 
 fn set_skill_header_msg(code_skill: &String, skill_record: &SkillCodeRecord, professions_store: &ProfessionStore, emoji_lookup: &HashMap<String, Emoji>, response: &mut MessageBuilder) {
+    if let Some(emoji) = emoji_lookup.get(&skill_record.primary_profession.to_string()) {
+        response.emoji(emoji);
+    }
     response
-        .emoji(emoji_lookup.get(&skill_record.primary_profession.to_string()).unwrap())
         .push_bold(&professions_store.from(skill_record.primary_profession).unwrap().0)
         .push(" / ")
-        .push_bold(&professions_store.from(skill_record.secondary_profession).unwrap().0)
-        .emoji(emoji_lookup.get(&skill_record.secondary_profession.to_string()).unwrap())
+        .push_bold(&professions_store.from(skill_record.secondary_profession).unwrap().0);
+    if let Some(emoji) = emoji_lookup.get(&skill_record.secondary_profession.to_string()) {
+        response.emoji(emoji);
+    }
+    response
         .push("--")
         .push_mono(&code_skill)
         .push("--\n");
@@ -108,7 +156,7 @@ fn add_attribute_to_msg(skill_record: &SkillCodeRecord, attributes_store: &Attri
 fn get_embedded_build(ids: &[u32; 8]) -> ImageResult<DynamicImage> {
     let mut build_image = DynamicImage::new_rgb8(64 * 8, 64);
     for (i, id) in ids.iter().enumerate() {
-        let image = ImageReader::open(format!("cache/{}.jpg", id)).unwrap().decode()?;
+        let image = ImageReader::open(format!("cache/{}.jpg", id))?.decode()?;
         let mut skill_part = build_image.sub_image((i * 64) as u32, 0, 64, 64);
         skill_part.copy_from(&image, 0, 0)?;
     }
@@ -0,0 +1,238 @@
+use std::env;
+use std::time::Instant;
+
+use serenity::builder::CreateApplicationCommands;
+use serenity::json::Value;
+use serenity::model::application::command::{Command, CommandOptionType};
+use serenity::model::application::interaction::application_command::{ApplicationCommandInteraction, CommandDataOption};
+use serenity::model::application::interaction::autocomplete::AutocompleteInteraction;
+use serenity::model::application::interaction::Interaction;
+use serenity::model::id::GuildId;
+use serenity::prelude::*;
+use tracing::{error, info, Instrument};
+
+use crate::{get_bot_datas, get_mut_bot_datas};
+use crate::commands::audit::log_change;
+use crate::commands::bonus::bonus_reply;
+use crate::commands::bonusnext::{bonus_lookup_reply, bonusnext_reply};
+use crate::commands::event::event_reply;
+use crate::commands::lang::set_language;
+use crate::commands::nick::nick_reply;
+use crate::commands::nicknext::{gift_lookup_reply, nicknext_reply};
+use crate::commands::permissions::may_configure;
+use crate::commands::ratelimit::rate_limit_refusal;
+use crate::commands::reply::Reply;
+use crate::commands::rules::rule_refusal;
+use crate::commands::skill::skill_reply;
+use crate::commands::zq::{quest_rotations, zq_reply};
+use crate::commands::zqnext::{quest_lookup_reply, zqnext_reply};
+use crate::constants::{AUTOCOMPLETE_MAX_CHOICES, NICHOLAS_MAX_GIFTS};
+use crate::enums::Language;
+use crate::logging::command_span;
+
+/// Registers the slash commands, on `HOME_GUILD` only when it's set since guild commands update instantly.
+pub async fn register(ctx: &Context) {
+    let registered = match env::var("HOME_GUILD").ok().and_then(|guild| guild.parse::<u64>().ok()) {
+        Some(guild) => GuildId(guild).set_application_commands(&ctx.http, create_commands).await,
+        None => Command::set_global_application_commands(&ctx.http, create_commands).await,
+    };
+    if let Err(why) = registered {
+        error!(error = ?why, "Error registering slash commands");
+    }
+}
+
+fn create_commands(commands: &mut CreateApplicationCommands) -> &mut CreateApplicationCommands {
+    commands
+        .create_application_command(|c| c.name("skill").description("Show a skill or decode a build template")
+            .create_option(|o| o.name("query").description("Build template code or skill name")
+                .kind(CommandOptionType::String).required(true).set_autocomplete(true)))
+        .create_application_command(|c| c.name("zq").description("Today's Zaishen quests"))
+        .create_application_command(|c| c.name("zqnext").description("Tomorrow's Zaishen quests")
+            .create_option(|o| o.name("quest").description("Tell when this quest comes back instead")
+                .kind(CommandOptionType::String).set_autocomplete(true)))
+        .create_application_command(|c| c.name("nick").description("Nicholas the traveler this week")
+            .create_option(|o| o.name("gifts").description("Number of presents to collect items for")
+                .kind(CommandOptionType::Integer).min_int_value(1).max_int_value(NICHOLAS_MAX_GIFTS)))
+        .create_application_command(|c| c.name("nicknext").description("Nicholas the traveler next week")
+            .create_option(|o| o.name("gifts").description("Number of presents to collect items for")
+                .kind(CommandOptionType::Integer).min_int_value(1).max_int_value(NICHOLAS_MAX_GIFTS))
+            .create_option(|o| o.name("item").description("Tell when Nicholas collects this item instead")
+                .kind(CommandOptionType::String).set_autocomplete(true)))
+        .create_application_command(|c| c.name("bonus").description("This week's bonuses"))
+        .create_application_command(|c| c.name("bonusnext").description("Next week's bonuses")
+            .create_option(|o| o.name("bonus").description("Tell when this bonus comes back instead")
+                .kind(CommandOptionType::String).set_autocomplete(true)))
+        .create_application_command(|c| c.name("event").description("Running and upcoming special events"))
+        .create_application_command(|c| c.name("lang").description("Language the bot speaks on this server")
+            .create_option(|o| o.name("language").description("Language")
+                .kind(CommandOptionType::String).required(true).set_autocomplete(true)))
+}
+
+pub async fn handle(ctx: &Context, interaction: Interaction) {
+    match interaction {
+        Interaction::ApplicationCommand(command) => run(ctx, &command).await,
+        Interaction::Autocomplete(autocomplete) => autocomplete_options(ctx, &autocomplete).await,
+        _ => {}
+    }
+}
+
+fn string_option<'a>(options: &'a [CommandDataOption], name: &str) -> Option<&'a str> {
+    options.iter()
+        .find(|option| option.name == name)
+        .and_then(|option| option.value.as_ref())
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+}
+
+fn gifts_option(options: &[CommandDataOption]) -> Option<u32> {
+    options.iter()
+        .find(|option| option.name == "gifts")
+        .and_then(|option| option.value.as_ref())
+        .and_then(Value::as_u64)
+        .map(|gifts| gifts as u32)
+}
+
+/// Answers the slash command and traces how long it took and how it ended.
+async fn run(ctx: &Context, command: &ApplicationCommandInteraction) {
+    let span = command_span("slash", command.guild_id.map(|guild| guild.0), command.channel_id.0, command.user.id.0, &command.data.name);
+    let start = Instant::now();
+    let outcome = answer(ctx, command).instrument(span.clone()).await;
+    info!(parent: &span, latency_ms = start.elapsed().as_millis() as u64, outcome, "Command run");
+}
+
+/// Answers with the same reply as the prefix command of the same name, and tells how it ended.
+async fn answer(ctx: &Context, command: &ApplicationCommandInteraction) -> &'static str {
+    let guild = command.guild_id.map(|guild| guild.0).unwrap_or(0);
+    let channel = command.channel_id.0;
+    let user = command.user.id.0;
+    let options = &command.data.options;
+    let refusal = {
+        let datas_lock = get_bot_datas(ctx).await;
+        let read_data = datas_lock.read().await;
+        match rule_refusal(&read_data, guild, channel, user, &command.data.name) {
+            Some(refusal) => Some(("refused", refusal)),
+            // an interaction must be answered, even when the rate limit was already told
+            None => rate_limit_refusal(&read_data, guild, channel, user, &command.data.name).err()
+                .map(|refusal| ("rate_limited", refusal.reply)),
+        }
+    };
+    if let Some((outcome, refusal)) = refusal {
+        Reply::text(refusal).respond(ctx, command).await;
+        return outcome;
+    }
+    let mut outcome = "ok";
+    let mut deferred = false;
+    let reply = match command.data.name.as_str() {
+        "skill" => {
+            let query = string_option(options, "query").unwrap_or_default();
+            // fetching the server emojis and building the image can outlast the response deadline
+            deferred = match command.defer(&ctx.http).await {
+                Ok(()) => true,
+                Err(why) => {
+                    error!(command = %command.data.name, error = ?why, "Error deferring slash command");
+                    false
+                }
+            };
+            match skill_reply(ctx, guild, channel, user, query).await {
+                Ok(reply) => reply,
+                Err(why) => {
+                    outcome = "error";
+                    Reply::text(format!("Could not show {}: {}", query, why))
+                }
+            }
+        }
+        "lang" => {
+            let language = string_option(options, "language").unwrap_or_default();
+            let response = {
+                let datas_lock = get_mut_bot_datas(ctx).await;
+                let mut write_data = datas_lock.write().await;
+                // outside a server set_language answers by itself
+                let allowed = command.member.as_ref()
+                    .map(|member| may_configure(&write_data.guilds_config, guild, member.permissions.unwrap_or_default(), &member.roles))
+                    .unwrap_or(true);
+                if allowed {
+                    set_language(&mut write_data.guilds_config, command.guild_id.map(|guild| guild.0), language)
+                } else {
+                    let lang = write_data.language(guild, channel, user);
+                    Err(write_data.i18n_messages.lng(lang).unwrap().permission_denied().to_string())
+                }
+            };
+            if let (Ok(response), Some(guild)) = (&response, command.guild_id) {
+                log_change(ctx, guild.0, channel, &command.user, &format!("/lang {}", language), response).await;
+            }
+            Reply::text(response.unwrap_or_else(|why| why))
+        }
+        name => {
+            let datas_lock = get_bot_datas(ctx).await;
+            let read_data = &datas_lock.read().await;
+            match name {
+                "zq" => zq_reply(read_data, guild, channel, user),
+                "zqnext" => match string_option(options, "quest") {
+                    Some(quest) => quest_lookup_reply(read_data, guild, channel, user, quest),
+                    None => zqnext_reply(read_data, guild, channel, user),
+                },
+                "nick" => nick_reply(read_data, guild, channel, user, gifts_option(options)),
+                "nicknext" => match string_option(options, "item") {
+                    Some(item) => gift_lookup_reply(read_data, guild, channel, user, item),
+                    None => nicknext_reply(read_data, guild, channel, user, gifts_option(options)),
+                },
+                "bonus" => bonus_reply(read_data, guild, channel, user),
+                "bonusnext" => match string_option(options, "bonus") {
+                    Some(bonus) => bonus_lookup_reply(read_data, guild, channel, user, bonus),
+                    None => bonusnext_reply(read_data, guild, channel, user),
+                },
+                "event" => event_reply(read_data, guild, channel, user),
+                _ => Reply::text(format!("Unknown command /{}", name)),
+            }
+        }
+    };
+    if deferred {
+        reply.follow_up(ctx, command).await;
+    } else {
+        reply.respond(ctx, command).await;
+    }
+    outcome
+}
+
+/// Names containing `input`, those starting with it first.
+fn suggest<'a>(names: impl IntoIterator<Item=&'a str>, input: &str) -> Vec<&'a str> {
+    let input = input.to_lowercase();
+    let mut matches: Vec<_> = names.into_iter()
+        .filter(|name| name.to_lowercase().contains(&input))
+        .collect();
+    matches.sort_by_key(|name| (!name.to_lowercase().starts_with(&input), *name));
+    matches.dedup();
+    matches.truncate(AUTOCOMPLETE_MAX_CHOICES);
+    matches
+}
+
+async fn autocomplete_options(ctx: &Context, autocomplete: &AutocompleteInteraction) {
+    let focused = match autocomplete.data.options.iter().find(|option| option.focused) {
+        Some(option) => option,
+        None => return,
+    };
+    let input = focused.value.as_ref().and_then(Value::as_str).unwrap_or_default();
+    let guild = autocomplete.guild_id.map(|guild| guild.0).unwrap_or(0);
+    let choices: Vec<String> = {
+        let datas_lock = get_bot_datas(ctx).await;
+        let read_data = &datas_lock.read().await;
+        let lang = read_data.language(guild, autocomplete.channel_id.0, autocomplete.user.id.0);
+        let choices = match (autocomplete.data.name.as_str(), focused.name.as_str()) {
+            ("skill", "query") => suggest(read_data.skills.names(lang), input),
+            ("zqnext", "quest") => suggest(quest_rotations(read_data, lang).into_iter().flat_map(|(_, _, store)| store.names()), input),
+            ("nicknext", "item") => suggest(read_data.nicholas_traveler.lng(lang).unwrap().names(), input),
+            ("bonusnext", "bonus") => suggest(read_data.bonus_pve.lng(lang).unwrap().names()
+                                                  .chain(read_data.bonus_pvp.lng(lang).unwrap().names()), input),
+            ("lang", "language") => suggest(Language::ALL.iter().map(Language::name), input),
+            _ => vec![],
+        };
+        choices.into_iter().map(String::from).collect()
+    };
+    if let Err(why) = autocomplete.create_autocomplete_response(&ctx.http, |r| {
+        choices.iter().for_each(|choice| { r.add_string_choice(choice, choice); });
+        r
+    }).await {
+        error!(command = %autocomplete.data.name, error = ?why, "Error answering autocomplete");
+    }
+}
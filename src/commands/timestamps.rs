@@ -0,0 +1,45 @@
+use serenity::framework::standard::{Args, CommandResult};
+use serenity::framework::standard::macros::command;
+use serenity::model::channel::Message;
+use serenity::prelude::*;
+use tracing::error;
+
+use crate::commands::audit::log_change;
+use crate::commands::permissions::CONFIGURE_CHECK;
+use crate::get_mut_bot_datas;
+use crate::utils::GuildsConfig;
+use crate::utils::time::TimestampStyle;
+
+#[command]
+#[checks(Configure)]
+async fn timestamps(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let style = args.single::<String>()?;
+    let channel = msg.channel_id.to_channel(&ctx).await?.guild();
+
+    if let Some(channel) = channel {
+        let response = match style.parse::<TimestampStyle>() {
+            Ok(style) => {
+                let data_lock = get_mut_bot_datas(ctx).await;
+                let mut write_data = data_lock.write().await;
+                let config: &mut GuildsConfig = &mut write_data.guilds_config;
+                match config.set_timestamp_style(channel.guild_id.0, style) {
+                    Ok(()) if style == TimestampStyle::Discord => Ok("Dates on your server will now be shown in each reader's own time zone".to_string()),
+                    Ok(()) => Ok("Dates on your server will now be shown as plain text".to_string()),
+                    Err(why) => Err(format!("Could not save the date style: {}", why)),
+                }
+            }
+            Err(err) => Err(err),
+        };
+        let changed = response.is_ok();
+        let response = response.unwrap_or_else(|why| why);
+        if let Err(why) = msg.channel_id.say(&ctx.http, &response).await {
+            error!(error = ?why, "Error sending message");
+        }
+        if changed {
+            log_change(ctx, channel.guild_id.0, msg.channel_id.0, &msg.author, &msg.content, &response).await;
+        }
+    } else {
+        msg.channel_id.say(&ctx.http, "Can't configure outside a server").await.ok();
+    }
+    Ok(())
+}
@@ -0,0 +1,99 @@
+use serenity::framework::standard::CommandResult;
+use serenity::framework::standard::macros::command;
+use serenity::model::channel::Message;
+use serenity::prelude::*;
+use tracing::error;
+
+use crate::constants::{
+    BONUS_EVENT_PVE,
+    BONUS_EVENT_PVP,
+    NICHOLAS_TRAVELER,
+    PRE_SEARING,
+    SHINING_BLADE_BOUNTY,
+    VANGUARD_QUEST,
+    ZAISHEN_BOUNTY,
+    ZAISHEN_COMBAT,
+    ZAISHEN_MISSION,
+    ZAISHEN_VANQUISH,
+};
+use crate::get_bot_datas;
+use crate::utils::{BonusEventStore, I18nMessageStore, NicholasGiftStore, PreSearingGiftStore, SpecialEventStore, ZaishenQuestStore};
+use crate::utils::reset::ResetCycle;
+
+#[command]
+async fn today(ctx: &Context, msg: &Message) -> CommandResult {
+    let guild = msg.channel_id.to_channel(&ctx).await?.guild()
+        .map(|channel| channel.guild_id.0).unwrap_or(0);
+    let datas_lock = get_bot_datas(ctx).await;
+    let read_data = &datas_lock.read().await;
+    let lang = read_data.language(guild, msg.channel_id.0, msg.author.id.0);
+    let time = read_data.time_formatter(guild, msg.author.id.0);
+    let i18n_messages: &I18nMessageStore = read_data.i18n_messages.lng(lang).unwrap();
+    let now = read_data.clock.now();
+    let tomorrow = ResetCycle::Daily.next(now);
+    let next_week = ResetCycle::Weekly.next(now);
+
+    let quest = |store: &crate::I18nStore<ZaishenQuestStore>, id: i64| store.lng(lang).unwrap().get_from_id(id).unwrap().name.clone();
+    let zaishen = [
+        (i18n_messages.zaishen_quest_mission(), quest(&read_data.zaishen_mission, ZAISHEN_MISSION.index_at(now))),
+        (i18n_messages.zaishen_quest_bounty(), quest(&read_data.zaishen_bounty, ZAISHEN_BOUNTY.index_at(now))),
+        (i18n_messages.zaishen_quest_combat(), quest(&read_data.zaishen_combat, ZAISHEN_COMBAT.index_at(now))),
+        (i18n_messages.zaishen_quest_vanquish(), quest(&read_data.zaishen_vanquish, ZAISHEN_VANQUISH.index_at(now))),
+        (i18n_messages.vanguard_quest(), quest(&read_data.vanguard_quest, VANGUARD_QUEST.index_at(now))),
+        (i18n_messages.wanted_bounty(), quest(&read_data.shining_blade_bounty, SHINING_BLADE_BOUNTY.index_at(now))),
+    ];
+    let zaishen_field = zaishen.iter()
+        .map(|(label, name)| format!("{} **{}**", label, name))
+        .collect::<Vec<_>>().join("\n");
+
+    let nicholas_gift: &NicholasGiftStore = read_data.nicholas_traveler.lng(lang).unwrap();
+    let gift = nicholas_gift.get_from_id(NICHOLAS_TRAVELER.index_at(now)).unwrap();
+    let nicholas_field = format!("**{}** {} **{}**\n{} {} ({})", gift.item, i18n_messages.nicholas_gift_per(), gift.location,
+                                 i18n_messages.nicholas_gift_in(), gift.region, gift.campaign);
+    let pre_searing: &PreSearingGiftStore = read_data.pre_searing.lng(lang).unwrap();
+    let pre_searing_gift = pre_searing.get_from_id(PRE_SEARING.index_at(now)).unwrap();
+    let pre_searing_field = format!("**{}** {}", pre_searing_gift.item, i18n_messages.pre_searing_per());
+
+    let (calendar, event_store) = &read_data.event;
+    let localized_events: &SpecialEventStore = event_store.lng(lang).unwrap();
+    let running = calendar.periods_running(&now);
+    let bonus_pve: &BonusEventStore = read_data.bonus_pve.lng(lang).unwrap();
+    let bonus_pvp: &BonusEventStore = read_data.bonus_pvp.lng(lang).unwrap();
+    let pve = read_data.event_bonus.effective_pve(BONUS_EVENT_PVE.index_at(now), &running);
+    let pvp = read_data.event_bonus.effective_pvp(BONUS_EVENT_PVP.index_at(now), &running);
+    let bonus_field = pve.iter().filter_map(|(id, _)| bonus_pve.get_from_id(*id)).map(|bonus| (i18n_messages.bonus_pve(), bonus))
+        .chain(pvp.iter().filter_map(|(id, _)| bonus_pvp.get_from_id(*id)).map(|bonus| (i18n_messages.bonus_pvp(), bonus)))
+        .map(|(label, bonus)| format!("{} **{}**", label, bonus.name))
+        .collect::<Vec<_>>().join("\n");
+
+    let event_field = if running.is_empty() {
+        i18n_messages.event_no_running().to_string()
+    } else {
+        running.iter()
+            .filter_map(|period| localized_events.get_from_id(period.0).map(|event| (event, period)))
+            .map(|(event, period)| format!("**{}** -- {} {}", event.name, i18n_messages.today_event_until(), time.datetime(&period.1.1)))
+            .collect::<Vec<_>>().join("\n")
+    };
+
+    let countdown = |target| format!("**{}** ({})", time.countdown(&target, &now, i18n_messages.time_days()), time.datetime(&target));
+    let reset_field = format!("{} {}\n{} {}", i18n_messages.today_daily_reset(), countdown(tomorrow),
+                              i18n_messages.today_weekly_reset(), countdown(next_week));
+
+    if let Err(why) = msg.channel_id.send_message(&ctx.http, |m| {
+        m.embed(|e| {
+            e.title(i18n_messages.today_headline())
+                .field(i18n_messages.today_zaishen(), zaishen_field, false)
+                .field(i18n_messages.today_nicholas(), nicholas_field, false)
+                .field(i18n_messages.today_pre_searing(), pre_searing_field, false)
+                .field(i18n_messages.today_bonuses(), bonus_field, false)
+                .field(i18n_messages.today_events(), event_field, false)
+                .field(i18n_messages.today_resets(), reset_field, false)
+                .timestamp(now)
+        });
+        m
+    }).await {
+        error!(error = ?why, "Error sending message");
+    }
+
+    Ok(())
+}
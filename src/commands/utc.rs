@@ -2,23 +2,43 @@ use serenity::framework::standard::{Args, CommandResult};
 use serenity::framework::standard::macros::command;
 use serenity::model::channel::Message;
 use serenity::prelude::*;
-use serenity::utils::MessageBuilder;
+use tracing::error;
 
+use crate::commands::audit::log_change;
+use crate::commands::permissions::CONFIGURE_CHECK;
 use crate::get_mut_bot_datas;
 use crate::utils::GuildsConfig;
+use crate::utils::time::parse_timezone;
 
 #[command]
+#[checks(Configure)]
 async fn utc(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
-    let utc = args.single::<i32>()?;
-    let data_lock = get_mut_bot_datas(ctx).await;
-    let mut write_data = data_lock.write().await;
-    let config: &mut GuildsConfig = &mut write_data.guilds_config;
-    let channel = msg.channel_id.to_channel(&ctx).await?.guild().unwrap();
-    config.set_utc(channel.guild_id.0, utc);
-    let mut response = MessageBuilder::new();
-    response.push(format!("Command on your server will now use UTC{} as reference", utc));
-    if let Err(why) = msg.channel_id.say(&ctx.http, &response).await {
-        println!("Error sending message: {:?}", why);
+    let timezone = args.single::<String>()?;
+    let channel = msg.channel_id.to_channel(&ctx).await?.guild();
+
+    if let Some(channel) = channel {
+        let response = match parse_timezone(&timezone) {
+            Ok(tz) => {
+                let data_lock = get_mut_bot_datas(ctx).await;
+                let mut write_data = data_lock.write().await;
+                let config: &mut GuildsConfig = &mut write_data.guilds_config;
+                match config.set_timezone(channel.guild_id.0, tz) {
+                    Ok(()) => Ok(format!("Command on your server will now use {} as reference", tz.name())),
+                    Err(why) => Err(format!("Could not save the time zone: {}", why)),
+                }
+            }
+            Err(err) => Err(err),
+        };
+        let changed = response.is_ok();
+        let response = response.unwrap_or_else(|why| why);
+        if let Err(why) = msg.channel_id.say(&ctx.http, &response).await {
+            error!(error = ?why, "Error sending message");
+        }
+        if changed {
+            log_change(ctx, channel.guild_id.0, msg.channel_id.0, &msg.author, &msg.content, &response).await;
+        }
+    } else {
+        msg.channel_id.say(&ctx.http, "Can't configure outside a server").await.ok();
     }
     Ok(())
-}
\ No newline at end of file
+}
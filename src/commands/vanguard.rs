@@ -0,0 +1,51 @@
+use chrono::{DateTime, Utc};
+use serenity::framework::standard::CommandResult;
+use serenity::framework::standard::macros::command;
+use serenity::model::channel::Message;
+use serenity::prelude::*;
+use serenity::utils::MessageBuilder;
+use tracing::error;
+
+use crate::constants::VANGUARD_QUEST;
+use crate::get_bot_datas;
+use crate::utils::{I18nMessageStore, ZaishenQuestStore};
+use crate::utils::reset::{ResetCycle, Rotation};
+use crate::utils::time::TimeFormatter;
+
+#[command]
+async fn vanguard(ctx: &Context, msg: &Message) -> CommandResult {
+    let guild = msg.channel_id.to_channel(&ctx).await?.guild()
+        .map(|channel| channel.guild_id.0).unwrap_or(0);
+    let datas_lock = get_bot_datas(ctx).await;
+    let read_data = &datas_lock.read().await;
+    let lang = read_data.language(guild, msg.channel_id.0, msg.author.id.0);
+    let time = read_data.time_formatter(guild, msg.author.id.0);
+    let i18n_messages: &I18nMessageStore = read_data.i18n_messages.lng(lang).unwrap();
+    let vanguard_quest: &ZaishenQuestStore = read_data.vanguard_quest.lng(lang).unwrap();
+    let now = read_data.clock.now();
+    let response = build_daily_response(i18n_messages, i18n_messages.vanguard_headline(), vanguard_quest, VANGUARD_QUEST, &now, &time);
+
+    if let Err(why) = msg.channel_id.say(&ctx.http, &response).await {
+        error!(error = ?why, "Error sending message");
+    }
+
+    Ok(())
+}
+
+/// Today's and tomorrow's entry of a daily rotation with the countdown to the reset.
+pub fn build_daily_response(i18n_messages: &I18nMessageStore, headline: &str, store: &ZaishenQuestStore, rotation: Rotation, now: &DateTime<Utc>, time: &TimeFormatter) -> MessageBuilder {
+    let tomorrow = ResetCycle::Daily.next(*now);
+    let today_quest = store.get_from_id(rotation.index_at(*now)).unwrap();
+    let tomorrow_quest = store.get_from_id(rotation.index_at(tomorrow)).unwrap();
+    let mut response = MessageBuilder::new();
+    response
+        .push_underline_line(headline)
+        .push(format!("{} ", i18n_messages.daily_today()))
+        .push_bold_line(&today_quest.name)
+        .push(format!("{} ", i18n_messages.daily_tomorrow()))
+        .push_bold_line(&tomorrow_quest.name)
+        .push(i18n_messages.daily_reset())
+        .push_bold(format!(" {}!", time.countdown(&tomorrow, now, i18n_messages.time_days())))
+        .push_line(format!(" ({})", time.datetime(&tomorrow)));
+    response
+}
@@ -0,0 +1,30 @@
+use serenity::framework::standard::CommandResult;
+use serenity::framework::standard::macros::command;
+use serenity::model::channel::Message;
+use serenity::prelude::*;
+use tracing::error;
+
+use crate::commands::vanguard::build_daily_response;
+use crate::constants::SHINING_BLADE_BOUNTY;
+use crate::get_bot_datas;
+use crate::utils::{I18nMessageStore, ZaishenQuestStore};
+
+#[command]
+async fn wanted(ctx: &Context, msg: &Message) -> CommandResult {
+    let guild = msg.channel_id.to_channel(&ctx).await?.guild()
+        .map(|channel| channel.guild_id.0).unwrap_or(0);
+    let datas_lock = get_bot_datas(ctx).await;
+    let read_data = &datas_lock.read().await;
+    let lang = read_data.language(guild, msg.channel_id.0, msg.author.id.0);
+    let time = read_data.time_formatter(guild, msg.author.id.0);
+    let i18n_messages: &I18nMessageStore = read_data.i18n_messages.lng(lang).unwrap();
+    let shining_blade_bounty: &ZaishenQuestStore = read_data.shining_blade_bounty.lng(lang).unwrap();
+    let now = read_data.clock.now();
+    let response = build_daily_response(i18n_messages, i18n_messages.wanted_headline(), shining_blade_bounty, SHINING_BLADE_BOUNTY, &now, &time);
+
+    if let Err(why) = msg.channel_id.say(&ctx.http, &response).await {
+        error!(error = ?why, "Error sending message");
+    }
+
+    Ok(())
+}
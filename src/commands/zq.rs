@@ -1,84 +1,145 @@
-use chrono::{Date, TimeZone, Utc};
 use serenity::framework::standard::CommandResult;
 use serenity::framework::standard::macros::command;
 use serenity::model::channel::Message;
 use serenity::prelude::*;
+use serenity::builder::CreateEmbed;
 use serenity::utils::MessageBuilder;
 
 use crate::constants::{
-    ZAISHEN_BOUNTY_SIZE_CYCLE,
-    ZAISHEN_BOUNTY_START,
-    ZAISHEN_COMBAT_SIZE_CYCLE,
-    ZAISHEN_COMBAT_START,
-    ZAISHEN_MISSION_SIZE_CYCLE,
-    ZAISHEN_MISSION_START,
-    ZAISHEN_VANQUISH_SIZE_CYCLE,
-    ZAISHEN_VANQUISH_START,
+    SHINING_BLADE_BOUNTY,
+    VANGUARD_QUEST,
+    ZAISHEN_BOUNTY,
+    ZAISHEN_COMBAT,
+    ZAISHEN_MISSION,
+    ZAISHEN_VANQUISH,
 };
-use crate::get_bot_datas;
-use crate::utils::{I18nMessageStore, ZaishenQuestStore};
-use crate::utils::time::{get_daily_start, get_next_day, get_time_left};
-
-fn get_mz_cycle_start() -> Date<Utc> {
-    let zq = ZAISHEN_MISSION_START;
-    Utc.ymd(zq.0, zq.1, zq.2)
-}
-
-fn get_bz_cycle_start() -> Date<Utc> {
-    let zq = ZAISHEN_BOUNTY_START;
-    Utc.ymd(zq.0, zq.1, zq.2)
-}
-fn get_cz_cycle_start() -> Date<Utc> {
-    let zq = ZAISHEN_COMBAT_START;
-    Utc.ymd(zq.0, zq.1, zq.2)
-}
-fn get_vz_cycle_start() -> Date<Utc> {
-    let zq = ZAISHEN_VANQUISH_START;
-    Utc.ymd(zq.0, zq.1, zq.2)
-}
+use crate::{BotData, get_bot_datas};
+use crate::commands::reply::Reply;
+use crate::enums::Language;
+use crate::utils::{I18nMessageStore, ZaishenQuestData, ZaishenQuestStore};
+use crate::utils::reset::{ResetCycle, Rotation};
 
 #[command]
 async fn zq(ctx: &Context, msg: &Message) -> CommandResult {
     let guild = msg.channel_id.to_channel(&ctx).await?.guild()
         .and_then(|channel| Some(channel.guild_id.0)).unwrap_or(0);
-    let datas_lock = get_bot_datas(ctx).await;
-    let read_data = &datas_lock.read().await;
-    let (lang, _) = read_data.guilds_config.get_guild_config(guild);
+    let reply = {
+        let datas_lock = get_bot_datas(ctx).await;
+        let read_data = &datas_lock.read().await;
+        zq_reply(read_data, guild, msg.channel_id.0, msg.author.id.0)
+    };
+    reply.send(ctx, msg.channel_id).await;
+
+    Ok(())
+}
+
+pub fn zq_reply(read_data: &BotData, guild: u64, channel: u64, user: u64) -> Reply {
+    let lang = read_data.language(guild, channel, user);
+    let time = read_data.time_formatter(guild, user);
     let i18n_messages: &I18nMessageStore = &read_data.i18n_messages.lng(lang).unwrap();
     let zq_mission: &ZaishenQuestStore = &read_data.zaishen_mission.lng(lang).unwrap();
     let zq_bounty: &ZaishenQuestStore = &read_data.zaishen_bounty.lng(lang).unwrap();
     let zq_combat: &ZaishenQuestStore = &read_data.zaishen_combat.lng(lang).unwrap();
     let zq_vanquish: &ZaishenQuestStore = &read_data.zaishen_vanquish.lng(lang).unwrap();
-    let now = Utc::now();
-    let tomorrow = get_daily_start(get_next_day(now, 16));
-    let count_days = |since| now.signed_duration_since(get_daily_start(since)).num_days();
-    let mz_id = count_days(get_mz_cycle_start()) % ZAISHEN_MISSION_SIZE_CYCLE;
-    let bz_id = count_days(get_bz_cycle_start()) % ZAISHEN_BOUNTY_SIZE_CYCLE;
-    let cz_id = count_days(get_cz_cycle_start()) % ZAISHEN_COMBAT_SIZE_CYCLE;
-    let vz_id = count_days(get_vz_cycle_start()) % ZAISHEN_VANQUISH_SIZE_CYCLE;
-    let (_, hours_left, mins_left, secs_left) = get_time_left(tomorrow, now);
+    let vanguard_quest: &ZaishenQuestStore = read_data.vanguard_quest.lng(lang).unwrap();
+    let shining_blade_bounty: &ZaishenQuestStore = read_data.shining_blade_bounty.lng(lang).unwrap();
+    let now = read_data.clock.now();
+    let tomorrow = ResetCycle::Daily.next(now);
+    let mz_id = ZAISHEN_MISSION.index_at(now);
+    let bz_id = ZAISHEN_BOUNTY.index_at(now);
+    let cz_id = ZAISHEN_COMBAT.index_at(now);
+    let vz_id = ZAISHEN_VANQUISH.index_at(now);
+    let vanguard_id = VANGUARD_QUEST.index_at(now);
+    let wanted_id = SHINING_BLADE_BOUNTY.index_at(now);
 
     let mission = zq_mission.get_from_id(mz_id).unwrap();
     let bounty = zq_bounty.get_from_id(bz_id).unwrap();
     let combat = zq_combat.get_from_id(cz_id).unwrap();
     let vanquish = zq_vanquish.get_from_id(vz_id).unwrap();
+    let vanguard = vanguard_quest.get_from_id(vanguard_id).unwrap();
+    let wanted = shining_blade_bounty.get_from_id(wanted_id).unwrap();
+    let embeds = vec![
+        quest_embed(i18n_messages, i18n_messages.zaishen_quest_mission(), mission),
+        bounty_embed(read_data, lang, i18n_messages.zaishen_quest_bounty(), bz_id, bounty),
+        quest_embed(i18n_messages, i18n_messages.zaishen_quest_combat(), combat),
+        quest_embed(i18n_messages, i18n_messages.zaishen_quest_vanquish(), vanquish),
+        quest_embed(i18n_messages, i18n_messages.vanguard_quest(), vanguard),
+        quest_embed(i18n_messages, i18n_messages.wanted_bounty(), wanted),
+    ];
     let mut response = MessageBuilder::new();
     response
         .push_underline_line(i18n_messages.zaishen_quest_headline())
-        .push(format!("{} ", i18n_messages.zaishen_quest_mission()))
-        .push_bold_line(&mission.name)
-        .push(format!("{} ", i18n_messages.zaishen_quest_bounty()))
-        .push_bold_line(&bounty.name)
-        .push(format!("{} ", i18n_messages.zaishen_quest_combat()))
-        .push_bold_line(&combat.name)
-        .push(format!("{} ", i18n_messages.zaishen_quest_vanquish()))
-        .push_bold_line(&vanquish.name)
         .push(i18n_messages.zaishen_quest_reset())
-        .push_bold_line(format!(" {:0>2}:{:0>2}:{:0>2}!", hours_left, mins_left, secs_left));
+        .push_bold(format!(" {}!", time.countdown(&tomorrow, &now, i18n_messages.time_days())))
+        .push_line(format!(" ({})", time.datetime(&tomorrow)));
+
+    Reply { content: response.build(), embeds, ..Reply::default() }
+}
+
+/// Every daily quest rotation with its label, in the order they are displayed.
+pub fn quest_rotations(read_data: &BotData, lang: Language) -> Vec<(&str, Rotation, &ZaishenQuestStore)> {
+    let i18n_messages: &I18nMessageStore = read_data.i18n_messages.lng(lang).unwrap();
+    vec![
+        (i18n_messages.zaishen_quest_mission(), ZAISHEN_MISSION, read_data.zaishen_mission.lng(lang).unwrap()),
+        (i18n_messages.zaishen_quest_bounty(), ZAISHEN_BOUNTY, read_data.zaishen_bounty.lng(lang).unwrap()),
+        (i18n_messages.zaishen_quest_combat(), ZAISHEN_COMBAT, read_data.zaishen_combat.lng(lang).unwrap()),
+        (i18n_messages.zaishen_quest_vanquish(), ZAISHEN_VANQUISH, read_data.zaishen_vanquish.lng(lang).unwrap()),
+        (i18n_messages.vanguard_quest(), VANGUARD_QUEST, read_data.vanguard_quest.lng(lang).unwrap()),
+        (i18n_messages.wanted_bounty(), SHINING_BLADE_BOUNTY, read_data.shining_blade_bounty.lng(lang).unwrap()),
+    ]
+}
 
-    if let Err(why) = msg.channel_id.say(&ctx.http, &response).await {
-        println!("Error sending message: {:?}", why);
+/// One embed per quest, linking to the wiki with the map as thumbnail when known.
+pub fn quest_embed(i18n_messages: &I18nMessageStore, label: &str, quest: &ZaishenQuestData) -> CreateEmbed {
+    let mut embed = CreateEmbed::default();
+    embed
+        .author(|a| a.name(label))
+        .title(&quest.name);
+    if let Some(wiki_url) = &quest.wiki_url {
+        embed.url(wiki_url);
     }
-    
-    Ok(())
-}
\ No newline at end of file
+    let mut details = vec![];
+    let location: Vec<_> = [&quest.region, &quest.campaign].iter()
+        .filter_map(|detail| detail.as_deref())
+        .collect();
+    if !location.is_empty() {
+        details.push(location.join(" -- "));
+    }
+    if let Some(outpost) = &quest.outpost {
+        details.push(format!("{} **{}**", i18n_messages.zaishen_quest_outpost(), outpost));
+    }
+    if let Some(reward) = &quest.reward {
+        details.push(format!("{} **{} {}**", i18n_messages.zaishen_quest_reward(), reward, i18n_messages.zaishen_quest_coins()));
+    }
+    if !details.is_empty() {
+        embed.description(details.join("\n"));
+    }
+    if let Some(map_url) = &quest.map_url {
+        embed.thumbnail(map_url);
+    }
+    embed
+}
+
+/// The bounty embed also tells where the boss is, its profession and which elites a Signet of Capture can take from it.
+pub fn bounty_embed(read_data: &BotData, lang: Language, label: &str, bounty_id: i64, quest: &ZaishenQuestData) -> CreateEmbed {
+    let i18n_messages: &I18nMessageStore = read_data.i18n_messages.lng(lang).unwrap();
+    let mut embed = quest_embed(i18n_messages, label, quest);
+    let boss = match read_data.bounty_bosses.get_from_id(bounty_id) {
+        Some(boss) => boss,
+        None => return embed,
+    };
+    if let Some(location) = &boss.location {
+        embed.field(i18n_messages.zaishen_quest_location(), location, true);
+    }
+    if let Some(profession) = boss.profession.and_then(|profession| read_data.professions.lng(lang).unwrap().from(profession)) {
+        embed.field(i18n_messages.zaishen_quest_profession(), &profession.0, true);
+    }
+    let elites: Vec<_> = boss.elites.iter()
+        .filter_map(|skill| read_data.skills.lang_and_id(lang, *skill))
+        .map(|(skill, _)| skill.name.clone())
+        .collect();
+    if !elites.is_empty() {
+        embed.field(i18n_messages.zaishen_quest_capture(), elites.join(", "), true);
+    }
+    embed
+}
@@ -0,0 +1,70 @@
+use serenity::framework::standard::{Args, CommandResult};
+use serenity::framework::standard::macros::command;
+use serenity::model::channel::Message;
+use serenity::prelude::*;
+
+use crate::commands::forecast::{parse_count, send_count_error, send_forecast};
+use crate::constants::{
+    FORECAST_DEFAULT_DAYS,
+    FORECAST_MAX_DAYS,
+    PRE_SEARING,
+    SHINING_BLADE_BOUNTY,
+    VANGUARD_QUEST,
+    ZAISHEN_BOUNTY,
+    ZAISHEN_COMBAT,
+    ZAISHEN_MISSION,
+    ZAISHEN_VANQUISH,
+};
+use crate::get_bot_datas;
+use crate::utils::{I18nMessageStore, PreSearingGiftStore, ZaishenQuestStore};
+use crate::utils::reset::ResetCycle;
+use crate::utils::time::format_datetime;
+
+#[command]
+async fn zqforecast(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let guild = msg.channel_id.to_channel(&ctx).await?.guild()
+        .map(|channel| channel.guild_id.0).unwrap_or(0);
+    let days = match parse_count(&mut args, FORECAST_DEFAULT_DAYS, FORECAST_MAX_DAYS) {
+        Some(days) => days,
+        None => {
+            send_count_error(ctx, msg, guild, I18nMessageStore::forecast_days, FORECAST_MAX_DAYS).await;
+            return Ok(());
+        }
+    };
+    let (headline, page_label, entries) = {
+        let datas_lock = get_bot_datas(ctx).await;
+        let read_data = &datas_lock.read().await;
+        let lang = read_data.language(guild, msg.channel_id.0, msg.author.id.0);
+        let tz = read_data.timezone(guild, msg.author.id.0);
+        let i18n_messages: &I18nMessageStore = read_data.i18n_messages.lng(lang).unwrap();
+        let zaishen_mission: &ZaishenQuestStore = read_data.zaishen_mission.lng(lang).unwrap();
+        let zaishen_bounty: &ZaishenQuestStore = read_data.zaishen_bounty.lng(lang).unwrap();
+        let zaishen_combat: &ZaishenQuestStore = read_data.zaishen_combat.lng(lang).unwrap();
+        let zaishen_vanquish: &ZaishenQuestStore = read_data.zaishen_vanquish.lng(lang).unwrap();
+        let vanguard_quest: &ZaishenQuestStore = read_data.vanguard_quest.lng(lang).unwrap();
+        let shining_blade_bounty: &ZaishenQuestStore = read_data.shining_blade_bounty.lng(lang).unwrap();
+        let pre_searing: &PreSearingGiftStore = read_data.pre_searing.lng(lang).unwrap();
+        let first_day = ResetCycle::Daily.previous(read_data.clock.now());
+        let entries = (0..days)
+            .map(|day| first_day + ResetCycle::Daily.period() * day as i32)
+            .map(|reset| {
+                let quests = [
+                    (i18n_messages.zaishen_quest_mission(), &zaishen_mission.get_from_id(ZAISHEN_MISSION.index_at(reset)).unwrap().name),
+                    (i18n_messages.zaishen_quest_bounty(), &zaishen_bounty.get_from_id(ZAISHEN_BOUNTY.index_at(reset)).unwrap().name),
+                    (i18n_messages.zaishen_quest_combat(), &zaishen_combat.get_from_id(ZAISHEN_COMBAT.index_at(reset)).unwrap().name),
+                    (i18n_messages.zaishen_quest_vanquish(), &zaishen_vanquish.get_from_id(ZAISHEN_VANQUISH.index_at(reset)).unwrap().name),
+                    (i18n_messages.vanguard_quest(), &vanguard_quest.get_from_id(VANGUARD_QUEST.index_at(reset)).unwrap().name),
+                    (i18n_messages.wanted_bounty(), &shining_blade_bounty.get_from_id(SHINING_BLADE_BOUNTY.index_at(reset)).unwrap().name),
+                    (i18n_messages.forecast_pre_searing(), &pre_searing.get_from_id(PRE_SEARING.index_at(reset)).unwrap().item),
+                ];
+                let quests = quests.iter()
+                    .map(|(label, name)| format!("{} **{}**", label, name))
+                    .collect::<Vec<_>>().join("\n");
+                (format_datetime(&reset, &tz), quests)
+            })
+            .collect();
+        (i18n_messages.forecast_zaishen_headline().to_string(), i18n_messages.forecast_page().to_string(), entries)
+    };
+
+    send_forecast(ctx, msg, &headline, &page_label, entries).await
+}
@@ -1,84 +1,96 @@
-use chrono::{Date, TimeZone, Utc};
-use serenity::framework::standard::CommandResult;
+use serenity::framework::standard::{Args, CommandResult};
 use serenity::framework::standard::macros::command;
 use serenity::model::channel::Message;
 use serenity::prelude::*;
 use serenity::utils::MessageBuilder;
 
 use crate::constants::{
-    ZAISHEN_BOUNTY_SIZE_CYCLE,
-    ZAISHEN_BOUNTY_START,
-    ZAISHEN_COMBAT_SIZE_CYCLE,
-    ZAISHEN_COMBAT_START,
-    ZAISHEN_MISSION_SIZE_CYCLE,
-    ZAISHEN_MISSION_START,
-    ZAISHEN_VANQUISH_SIZE_CYCLE,
-    ZAISHEN_VANQUISH_START,
+    SHINING_BLADE_BOUNTY,
+    VANGUARD_QUEST,
+    ZAISHEN_BOUNTY,
+    ZAISHEN_COMBAT,
+    ZAISHEN_MISSION,
+    ZAISHEN_VANQUISH,
 };
-use crate::get_bot_datas;
+use crate::{BotData, get_bot_datas};
+use crate::commands::forecast::occurrence_reply;
+use crate::commands::reply::Reply;
+use crate::commands::zq::{bounty_embed, quest_embed, quest_rotations};
 use crate::utils::{I18nMessageStore, ZaishenQuestStore};
-use crate::utils::time::{get_daily_start, get_next_day, get_time_left};
-
-fn get_mz_cycle_start() -> Date<Utc> {
-    let zq = ZAISHEN_MISSION_START;
-    Utc.ymd(zq.0, zq.1, zq.2)
-}
-
-fn get_bz_cycle_start() -> Date<Utc> {
-    let zq = ZAISHEN_BOUNTY_START;
-    Utc.ymd(zq.0, zq.1, zq.2)
-}
-fn get_cz_cycle_start() -> Date<Utc> {
-    let zq = ZAISHEN_COMBAT_START;
-    Utc.ymd(zq.0, zq.1, zq.2)
-}
-fn get_vz_cycle_start() -> Date<Utc> {
-    let zq = ZAISHEN_VANQUISH_START;
-    Utc.ymd(zq.0, zq.1, zq.2)
-}
+use crate::utils::reset::ResetCycle;
 
 #[command]
-async fn zqnext(ctx: &Context, msg: &Message) -> CommandResult {
+async fn zqnext(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    let quest = args.rest().trim();
     let guild = msg.channel_id.to_channel(&ctx).await?.guild()
         .and_then(|channel| Some(channel.guild_id.0)).unwrap_or(0);
-    let datas_lock = get_bot_datas(ctx).await;
-    let read_data = &datas_lock.read().await;
-    let (lang, _) = read_data.guilds_config.get_guild_config(guild);
+    let reply = {
+        let datas_lock = get_bot_datas(ctx).await;
+        let read_data = &datas_lock.read().await;
+        if quest.is_empty() {
+            zqnext_reply(read_data, guild, msg.channel_id.0, msg.author.id.0)
+        } else {
+            quest_lookup_reply(read_data, guild, msg.channel_id.0, msg.author.id.0, quest)
+        }
+    };
+    reply.send(ctx, msg.channel_id).await;
+
+    Ok(())
+}
+
+pub fn zqnext_reply(read_data: &BotData, guild: u64, channel: u64, user: u64) -> Reply {
+    let lang = read_data.language(guild, channel, user);
+    let time = read_data.time_formatter(guild, user);
     let i18n_messages: &I18nMessageStore = &read_data.i18n_messages.lng(lang).unwrap();
     let zq_mission: &ZaishenQuestStore = &read_data.zaishen_mission.lng(lang).unwrap();
     let zq_bounty: &ZaishenQuestStore = &read_data.zaishen_bounty.lng(lang).unwrap();
     let zq_combat: &ZaishenQuestStore = &read_data.zaishen_combat.lng(lang).unwrap();
     let zq_vanquish: &ZaishenQuestStore = &read_data.zaishen_vanquish.lng(lang).unwrap();
-    let now = Utc::now();
-    let tomorrow = get_daily_start(get_next_day(now, 16));
-    let count_days = |since| tomorrow.signed_duration_since(get_daily_start(since)).num_days();
-    let mz_id = count_days(get_mz_cycle_start()) % ZAISHEN_MISSION_SIZE_CYCLE;
-    let bz_id = count_days(get_bz_cycle_start()) % ZAISHEN_BOUNTY_SIZE_CYCLE;
-    let cz_id = count_days(get_cz_cycle_start()) % ZAISHEN_COMBAT_SIZE_CYCLE;
-    let vz_id = count_days(get_vz_cycle_start()) % ZAISHEN_VANQUISH_SIZE_CYCLE;
-    let (_, hours_left, mins_left, secs_left) = get_time_left(tomorrow, now);
+    let vanguard_quest: &ZaishenQuestStore = read_data.vanguard_quest.lng(lang).unwrap();
+    let shining_blade_bounty: &ZaishenQuestStore = read_data.shining_blade_bounty.lng(lang).unwrap();
+    let now = read_data.clock.now();
+    let tomorrow = ResetCycle::Daily.next(now);
+    let mz_id = ZAISHEN_MISSION.index_at(tomorrow);
+    let bz_id = ZAISHEN_BOUNTY.index_at(tomorrow);
+    let cz_id = ZAISHEN_COMBAT.index_at(tomorrow);
+    let vz_id = ZAISHEN_VANQUISH.index_at(tomorrow);
+    let vanguard_id = VANGUARD_QUEST.index_at(tomorrow);
+    let wanted_id = SHINING_BLADE_BOUNTY.index_at(tomorrow);
 
     let mission = zq_mission.get_from_id(mz_id).unwrap();
     let bounty = zq_bounty.get_from_id(bz_id).unwrap();
     let combat = zq_combat.get_from_id(cz_id).unwrap();
     let vanquish = zq_vanquish.get_from_id(vz_id).unwrap();
+    let vanguard = vanguard_quest.get_from_id(vanguard_id).unwrap();
+    let wanted = shining_blade_bounty.get_from_id(wanted_id).unwrap();
+    let embeds = vec![
+        quest_embed(i18n_messages, i18n_messages.zaishen_quest_mission(), mission),
+        bounty_embed(read_data, lang, i18n_messages.zaishen_quest_bounty(), bz_id, bounty),
+        quest_embed(i18n_messages, i18n_messages.zaishen_quest_combat(), combat),
+        quest_embed(i18n_messages, i18n_messages.zaishen_quest_vanquish(), vanquish),
+        quest_embed(i18n_messages, i18n_messages.vanguard_quest(), vanguard),
+        quest_embed(i18n_messages, i18n_messages.wanted_bounty(), wanted),
+    ];
     let mut response = MessageBuilder::new();
     response
         .push_underline_line(i18n_messages.zaishen_quest_tomorrow_headline())
-        .push(format!("{} ", i18n_messages.zaishen_quest_mission()))
-        .push_bold_line(&mission.name)
-        .push(format!("{} ", i18n_messages.zaishen_quest_bounty()))
-        .push_bold_line(&bounty.name)
-        .push(format!("{} ", i18n_messages.zaishen_quest_combat()))
-        .push_bold_line(&combat.name)
-        .push(format!("{} ", i18n_messages.zaishen_quest_vanquish()))
-        .push_bold_line(&vanquish.name)
         .push(i18n_messages.zaishen_quest_reset())
-        .push_bold_line(format!(" {:0>2}:{:0>2}:{:0>2}!", hours_left, mins_left, secs_left));
+        .push_bold(format!(" {}!", time.countdown(&tomorrow, &now, i18n_messages.time_days())))
+        .push_line(format!(" ({})", time.datetime(&tomorrow)));
 
-    if let Err(why) = msg.channel_id.say(&ctx.http, &response).await {
-        println!("Error sending message: {:?}", why);
+    Reply { content: response.build(), embeds, ..Reply::default() }
+}
+
+/// Looks a quest up in every daily rotation and tells when it comes back.
+pub fn quest_lookup_reply(read_data: &BotData, guild: u64, channel: u64, user: u64, name: &str) -> Reply {
+    let lang = read_data.language(guild, channel, user);
+    let found = quest_rotations(read_data, lang).into_iter()
+        .find_map(|(label, rotation, store)| {
+            let id = store.find_by_name(name)?;
+            Some((label, rotation, id, store.get_from_id(id)?.name.as_str()))
+        });
+    match found {
+        Some((label, rotation, id, quest)) => occurrence_reply(read_data, guild, channel, user, label, quest, Some((rotation, id))),
+        None => occurrence_reply(read_data, guild, channel, user, "", name, None),
     }
-    
-    Ok(())
-}
\ No newline at end of file
+}
@@ -1,6 +1,17 @@
-const INVALID_VALUE: u8 = 255;
+use chrono_tz::Tz;
+
+use crate::utils::reset::Rotation;
+
+pub const INVALID_VALUE: u8 = 255;
 pub const DATETIME_FORMAT: &str = "%F %X %Z";
 
+pub const DAILY_RESET_HOUR: u32 = 16;
+pub const WEEKLY_RESET_HOUR: u32 = 15;
+// special events start and end at fixed Pacific times
+pub const SPECIAL_EVENT_TIMEZONE: Tz = Tz::America__Los_Angeles;
+// China Standard Time, the reference of the lunar calendar
+pub const LUNAR_CALENDAR_OFFSET_HOURS: i64 = 8;
+
 pub const ZAISHEN_MISSION_START: (i32, u32, u32) = (2011, 3, 3);
 pub const ZAISHEN_MISSION_SIZE_CYCLE: i64 = 69;
 pub const ZAISHEN_BOUNTY_START: (i32, u32, u32) = (2009, 6, 11);
@@ -9,11 +20,52 @@ pub const ZAISHEN_COMBAT_START: (i32, u32, u32) = (2009, 10, 22);
 pub const ZAISHEN_COMBAT_SIZE_CYCLE: i64 = 28;
 pub const ZAISHEN_VANQUISH_START: (i32, u32, u32) = (2017, 2, 15);
 pub const ZAISHEN_VANQUISH_SIZE_CYCLE: i64 = 136;
+// the Vanguard, Shining Blade and Nicholas Sandford origins are those of the daily quests in GWToolbox++
+// (https://github.com/gwdevhub/GWToolboxpp), the first entries of their CSVs fall on these days
+pub const VANGUARD_QUEST_START: (i32, u32, u32) = (2011, 3, 3);
+pub const VANGUARD_QUEST_SIZE_CYCLE: i64 = 9;
+pub const SHINING_BLADE_BOUNTY_START: (i32, u32, u32) = (2010, 6, 8);
+pub const SHINING_BLADE_BOUNTY_SIZE_CYCLE: i64 = 21;
 pub const BONUS_EVENT_START: (i32, u32, u32) = (2020, 8, 10);
 pub const BONUS_EVENT_PVE_SIZE_CYCLE: i64 = 9;
 pub const BONUS_EVENT_PVP_SIZE_CYCLE: i64 = 6;
 pub const NICHOLAS_TRAVELER_START: (i32, u32, u32) = (2018, 1, 22);
 pub const NICHOLAS_TRAVELER_SIZE_CYCLE: i64 = 137;
+pub const PRE_SEARING_START: (i32, u32, u32) = (2009, 4, 9);
+pub const PRE_SEARING_SIZE_CYCLE: i64 = 52;
+
+pub const ZAISHEN_MISSION: Rotation = Rotation::daily(ZAISHEN_MISSION_START, ZAISHEN_MISSION_SIZE_CYCLE);
+pub const ZAISHEN_BOUNTY: Rotation = Rotation::daily(ZAISHEN_BOUNTY_START, ZAISHEN_BOUNTY_SIZE_CYCLE);
+pub const ZAISHEN_COMBAT: Rotation = Rotation::daily(ZAISHEN_COMBAT_START, ZAISHEN_COMBAT_SIZE_CYCLE);
+pub const ZAISHEN_VANQUISH: Rotation = Rotation::daily(ZAISHEN_VANQUISH_START, ZAISHEN_VANQUISH_SIZE_CYCLE);
+pub const VANGUARD_QUEST: Rotation = Rotation::daily(VANGUARD_QUEST_START, VANGUARD_QUEST_SIZE_CYCLE);
+pub const SHINING_BLADE_BOUNTY: Rotation = Rotation::daily(SHINING_BLADE_BOUNTY_START, SHINING_BLADE_BOUNTY_SIZE_CYCLE);
+pub const BONUS_EVENT_PVE: Rotation = Rotation::weekly(BONUS_EVENT_START, BONUS_EVENT_PVE_SIZE_CYCLE);
+pub const BONUS_EVENT_PVP: Rotation = Rotation::weekly(BONUS_EVENT_START, BONUS_EVENT_PVP_SIZE_CYCLE);
+pub const NICHOLAS_TRAVELER: Rotation = Rotation::weekly(NICHOLAS_TRAVELER_START, NICHOLAS_TRAVELER_SIZE_CYCLE);
+pub const PRE_SEARING: Rotation = Rotation::daily(PRE_SEARING_START, PRE_SEARING_SIZE_CYCLE);
+
+pub const COUNTDOWN_MAX_DAYS: i64 = 31;
+pub const COUNTDOWN_TICK: std::time::Duration = std::time::Duration::from_secs(1);
+
+// overridden by the RATE_LIMITS environment variable, `<command>=<uses>/<seconds>/<user|channel>` separated by commas
+pub const DEFAULT_RATE_LIMITS: &str = "skill=3/30/user,menu=1/60/channel";
+
+pub const DEFAULT_PREFIX: &str = "-";
+pub const PREFIX_MAX_LENGTH: usize = 5;
+
+pub const AUTOCOMPLETE_MAX_CHOICES: usize = 25;
+
+pub const FORECAST_PAGE_SIZE: usize = 7;
+pub const FORECAST_DEFAULT_DAYS: i64 = 7;
+pub const FORECAST_MAX_DAYS: i64 = 63;
+pub const FORECAST_DEFAULT_WEEKS: i64 = 4;
+pub const FORECAST_MAX_WEEKS: i64 = 52;
+
+pub const NICHOLAS_MAX_GIFTS: u32 = 1000;
+pub const NICHOLAS_HISTORY_DEFAULT_WEEKS: i64 = 10;
+pub const NICHOLAS_REMINDER_HOURS: i64 = 12;
+pub const NICHOLAS_REMINDER_TICK: std::time::Duration = std::time::Duration::from_secs(60);
 
 pub const REACTION_ONE: &str = "1\u{fe0f}\u{20e3}";
 pub const REACTION_TWO: &str = "2\u{fe0f}\u{20e3}";
@@ -1,5 +1,6 @@
 use std::fmt::{Display, Formatter};
 use std::fmt;
+use std::str::FromStr;
 
 #[derive(Debug, PartialOrd, PartialEq, Ord, Eq, Clone, Hash, Copy)]
 pub enum Language {
@@ -8,15 +9,45 @@ pub enum Language {
 }
 
 impl Language {
-    pub fn from(s: &str) -> Result<Self, String> {
-        match s.to_lowercase().as_str() {
-            "fr" | "fr_fr" | "french" => Ok(Language::French),
-            "en" | "en_US" | "english" => Ok(Language::English),
+    pub const ALL: [Language; 2] = [Language::English, Language::French];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Language::French => "French",
+            Language::English => "English",
+        }
+    }
+
+    /// BCP-47 locale code, which is how the language is persisted.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Language::French => "fr-FR",
+            Language::English => "en-US",
+        }
+    }
+}
+
+impl FromStr for Language {
+    type Err = String;
+
+    /// Accepts locale codes with any region (`fr-FR`, `en_GB`, `fr`) and the names saved before the codes (`French`).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let code = s.trim().replace('_', "-").to_lowercase();
+        let primary = code.split('-').next().unwrap_or_default();
+        match (code.as_str(), primary) {
+            ("french", _) | (_, "fr") => Ok(Language::French),
+            ("english", _) | (_, "en") => Ok(Language::English),
             _ => Err(format!("Could not convert '{}' into a known language", s))
         }
     }
 }
 
+impl Display for Language {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.code())
+    }
+}
+
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
 pub enum ProfessionType {
     None,
@@ -38,25 +69,32 @@ impl Display for ProfessionType {
     }
 }
 
-impl From<u32> for ProfessionType{
-    fn from(n: u32) -> Self {
+impl ProfessionType {
+    /// The profession with this id in a build template, `None` for an unknown id.
+    pub fn from_id(n: u32) -> Option<Self> {
         match n {
-            0  => ProfessionType::None,
-            1  => ProfessionType::Warrior,
-            2  => ProfessionType::Ranger,
-            3  => ProfessionType::Monk,
-            4  => ProfessionType::Necromancer,
-            5  => ProfessionType::Mesmer,
-            6  => ProfessionType::Elementalist,
-            7  => ProfessionType::Assassin,
-            8  => ProfessionType::Ritualist,
-            9  => ProfessionType::Paragon,
-            10 => ProfessionType::Dervish,
-            _ => panic!("unknown profession '{}'", n)
+            0  => Some(ProfessionType::None),
+            1  => Some(ProfessionType::Warrior),
+            2  => Some(ProfessionType::Ranger),
+            3  => Some(ProfessionType::Monk),
+            4  => Some(ProfessionType::Necromancer),
+            5  => Some(ProfessionType::Mesmer),
+            6  => Some(ProfessionType::Elementalist),
+            7  => Some(ProfessionType::Assassin),
+            8  => Some(ProfessionType::Ritualist),
+            9  => Some(ProfessionType::Paragon),
+            10 => Some(ProfessionType::Dervish),
+            _ => None
         }
     }
 }
 
+impl From<u32> for ProfessionType{
+    fn from(n: u32) -> Self {
+        ProfessionType::from_id(n).unwrap_or_else(|| panic!("unknown profession '{}'", n))
+    }
+}
+
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub enum AttributeType {
@@ -118,65 +156,89 @@ impl Display for AttributeType {
     }
 }
 
-impl From<u32> for AttributeType{
-    fn from(n: u32) -> Self {
+impl AttributeType {
+    /// The attribute with this id in a build template, `None` for an unknown id.
+    pub fn from_id(n: u32) -> Option<Self> {
         match n {
-            0 => AttributeType::FastCasting,
-            1 => AttributeType::IllusionMagic,
-            2 => AttributeType::DominationMagic,
-            3 => AttributeType::InspirationMagic,
-            4 => AttributeType::BloodMagic,
-            5 => AttributeType::DeathMagic,
-            6 => AttributeType::SoulReaping,
-            7 => AttributeType::Curses,
-            8 => AttributeType::AirMagic,
-            9 => AttributeType::EarthMagic,
-            10 => AttributeType::FireMagic,
-            11 => AttributeType::WaterMagic,
-            12 => AttributeType::EnergyStorage,
-            13 => AttributeType::HealingPrayers,
-            14 => AttributeType::SmitingPrayers,
-            15 => AttributeType::ProtectionPrayers,
-            16 => AttributeType::DivineFavor,
-            17 => AttributeType::Strength,
-            18 => AttributeType::AxeMastery,
-            19 => AttributeType::HammerMastery,
-            20 => AttributeType::Swordsmanship,
-            21 => AttributeType::Tactics,
-            22 => AttributeType::BeastMastery,
-            23 => AttributeType::Expertise,
-            24 => AttributeType::WildernessSurvival,
-            25 => AttributeType::Marksmanship,
-            29 => AttributeType::DaggerMastery,
-            30 => AttributeType::DeadlyArts,
-            31 => AttributeType::ShadowArts,
-            32 => AttributeType::Communing,
-            33 => AttributeType::RestorationMagic,
-            34 => AttributeType::ChannelingMagic,
-            35 => AttributeType::CriticalStrikes,
-            36 => AttributeType::SpawningPower,
-            37 => AttributeType::SpearMastery,
-            38 => AttributeType::Command,
-            39 => AttributeType::Motivation,
-            40 => AttributeType::Leadership,
-            41 => AttributeType::ScytheMastery,
-            42 => AttributeType::WindPrayers,
-            43 => AttributeType::EarthPrayers,
-            44 => AttributeType::Mysticism,
-            90 => AttributeType::Luxon,
-            91 => AttributeType::Kurzick,
-            92 => AttributeType::Sunspear,
-            93 => AttributeType::Lightbringer,
-            94 => AttributeType::Norn,
-            95 => AttributeType::EbonVangard,
-            96 => AttributeType::Deldrimor,
-            97 => AttributeType::Asura,
-            999 => AttributeType::Other,
-            _ => panic!("Unknown attribute '{}'", n)
+            0 => Some(AttributeType::FastCasting),
+            1 => Some(AttributeType::IllusionMagic),
+            2 => Some(AttributeType::DominationMagic),
+            3 => Some(AttributeType::InspirationMagic),
+            4 => Some(AttributeType::BloodMagic),
+            5 => Some(AttributeType::DeathMagic),
+            6 => Some(AttributeType::SoulReaping),
+            7 => Some(AttributeType::Curses),
+            8 => Some(AttributeType::AirMagic),
+            9 => Some(AttributeType::EarthMagic),
+            10 => Some(AttributeType::FireMagic),
+            11 => Some(AttributeType::WaterMagic),
+            12 => Some(AttributeType::EnergyStorage),
+            13 => Some(AttributeType::HealingPrayers),
+            14 => Some(AttributeType::SmitingPrayers),
+            15 => Some(AttributeType::ProtectionPrayers),
+            16 => Some(AttributeType::DivineFavor),
+            17 => Some(AttributeType::Strength),
+            18 => Some(AttributeType::AxeMastery),
+            19 => Some(AttributeType::HammerMastery),
+            20 => Some(AttributeType::Swordsmanship),
+            21 => Some(AttributeType::Tactics),
+            22 => Some(AttributeType::BeastMastery),
+            23 => Some(AttributeType::Expertise),
+            24 => Some(AttributeType::WildernessSurvival),
+            25 => Some(AttributeType::Marksmanship),
+            29 => Some(AttributeType::DaggerMastery),
+            30 => Some(AttributeType::DeadlyArts),
+            31 => Some(AttributeType::ShadowArts),
+            32 => Some(AttributeType::Communing),
+            33 => Some(AttributeType::RestorationMagic),
+            34 => Some(AttributeType::ChannelingMagic),
+            35 => Some(AttributeType::CriticalStrikes),
+            36 => Some(AttributeType::SpawningPower),
+            37 => Some(AttributeType::SpearMastery),
+            38 => Some(AttributeType::Command),
+            39 => Some(AttributeType::Motivation),
+            40 => Some(AttributeType::Leadership),
+            41 => Some(AttributeType::ScytheMastery),
+            42 => Some(AttributeType::WindPrayers),
+            43 => Some(AttributeType::EarthPrayers),
+            44 => Some(AttributeType::Mysticism),
+            90 => Some(AttributeType::Luxon),
+            91 => Some(AttributeType::Kurzick),
+            92 => Some(AttributeType::Sunspear),
+            93 => Some(AttributeType::Lightbringer),
+            94 => Some(AttributeType::Norn),
+            95 => Some(AttributeType::EbonVangard),
+            96 => Some(AttributeType::Deldrimor),
+            97 => Some(AttributeType::Asura),
+            999 => Some(AttributeType::Other),
+            _ => None
         }
     }
 }
 
+impl From<u32> for AttributeType{
+    fn from(n: u32) -> Self {
+        AttributeType::from_id(n).unwrap_or_else(|| panic!("Unknown attribute '{}'", n))
+    }
+}
+
 
 
 // https://wiki.guildwars.com/images/e/e0/
+
+#[cfg(test)]
+mod test {
+    use crate::enums::Language;
+
+    #[test]
+    pub fn language_codes_round_trip() {
+        for language in Language::ALL {
+            assert_eq!(language.to_string().parse::<Language>(), Ok(language));
+            assert_eq!(language.name().parse::<Language>(), Ok(language));
+        }
+        assert_eq!("fr_FR".parse::<Language>(), Ok(Language::French));
+        assert_eq!("en_US".parse::<Language>(), Ok(Language::English));
+        assert_eq!("EN-gb".parse::<Language>(), Ok(Language::English));
+        assert!("de-DE".parse::<Language>().is_err());
+    }
+}
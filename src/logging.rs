@@ -0,0 +1,45 @@
+use std::collections::HashMap;
+use std::env;
+use std::sync::{Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+use tracing::{info_span, Span};
+use tracing_subscriber::EnvFilter;
+
+/// Filter used when `RUST_LOG` isn't set: the bot at info level, its dependencies only when something goes wrong.
+const DEFAULT_LOG_FILTER: &str = "warn,gw_bot=info";
+
+/// Installs the global subscriber. `LOG_FORMAT=json` writes one JSON object per line for log collectors,
+/// anything else the human readable format. `RUST_LOG` filters the events, with the `EnvFilter` syntax.
+pub fn init() {
+    let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(DEFAULT_LOG_FILTER));
+    let subscriber = tracing_subscriber::fmt().with_env_filter(filter);
+    let installed = match env::var("LOG_FORMAT").map(|format| format.to_lowercase()).as_deref() {
+        Ok("json") => subscriber.json().with_current_span(true).try_init(),
+        _ => subscriber.pretty().try_init(),
+    };
+    if let Err(why) = installed {
+        eprintln!("Could not install the logger: {}", why);
+    }
+}
+
+/// Span of a command invocation, `kind` tells the prefix commands from the slash ones.
+pub fn command_span(kind: &str, guild: Option<u64>, channel: u64, user: u64, command: &str) -> Span {
+    info_span!("command", kind, guild, channel, user, command)
+}
+
+/// Spans of the prefix commands being run, by message, from the `before` hook to the `after` one.
+#[derive(Debug, Default)]
+pub struct CommandTraces(Mutex<HashMap<u64, (Span, Instant)>>);
+
+impl CommandTraces {
+    pub fn start(&self, message: u64, span: Span) {
+        self.0.lock().unwrap_or_else(PoisonError::into_inner).insert(message, (span, Instant::now()));
+    }
+
+    /// The span of the command and how long it ran.
+    pub fn finish(&self, message: u64) -> Option<(Span, Duration)> {
+        self.0.lock().unwrap_or_else(PoisonError::into_inner).remove(&message)
+            .map(|(span, start)| (span, start.elapsed()))
+    }
+}
@@ -3,58 +3,201 @@ extern crate dotenv;
 use std::collections::HashMap;
 use std::env;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
 
 use dotenv::dotenv;
 use serenity::async_trait;
 use serenity::Client;
 use serenity::framework::standard::macros::{group, hook};
+use serenity::framework::standard::{CommandGroup, CommandResult, DispatchError};
 use serenity::framework::StandardFramework;
+use serenity::http::Http;
+use serenity::model::application::interaction::Interaction;
 use serenity::model::gateway::*;
 use serenity::model::prelude::Message;
 use serenity::prelude::*;
 use serenity::prelude::TypeMapKey;
+use tracing::{error, info, info_span, Instrument, Span};
+use chrono_tz::Tz;
 
 use commands::{
+    audit::*,
     bonus::*,
     bonusnext::*,
+    capture::*,
+    channellang::*,
+    configrole::*,
+    countdown::*,
     event::*,
+    help::*,
+    ics::*,
     lang::*,
     menu::*,
+    mylang::*,
+    myutc::*,
     nick::*,
     nicknext::*,
     ping::*,
+    pre::*,
+    prefix::*,
+    rules::*,
     skill::*,
-    // utc::*,
+    timestamps::*,
+    today::*,
+    zqforecast::*,
+    nickforecast::*,
+    bonusforecast::*,
+    utc::*,
+    vanguard::*,
+    wanted::*,
     zq::*,
     zqnext::*,
 };
 
+use crate::constants::DEFAULT_RATE_LIMITS;
 use crate::enums::Language;
-use crate::utils::{AttributeStore, BonusEventStore, get_special_events_time_range, GuildsConfig, I18nMessageStore, NicholasGiftStore, ProfessionStore, SKillI18nStore, SpecialEventPeriod, SpecialEventStore, ZaishenQuestStore};
+use crate::logging::CommandTraces;
+use crate::utils::reset::{Clock, SystemClock};
+use crate::utils::{AttributeStore, BonusEventStore, BountyBossStore, ChannelsConfig, EventBonusStore, GuildsConfig, I18nMessageStore, NicholasGiftStore, PreSearingGiftStore, ProfessionStore, SKillI18nStore, SpecialEventStore, UsersConfig, ZaishenQuestStore};
+use crate::utils::calendar::SpecialEventCalendar;
+use crate::utils::countdown::LiveCountdownStore;
+use crate::utils::database::{Database, DATABASE_PATH};
+use crate::utils::nicholas::NicholasTracker;
+use crate::utils::ratelimit::RateLimiter;
+use crate::utils::rules::CommandRules;
+use crate::utils::time::TimeFormatter;
 
 pub mod constants;
 pub mod enums;
+pub mod logging;
 mod commands;
 pub mod utils;
 
 #[group]
-#[commands(ping, skill, menu, zq, zqnext, bonus, bonusnext, nick, nicknext, event, lang)]
-struct General;
+#[commands(zq, zqnext, vanguard, wanted, bonus, bonusnext, nick, nicknext, pre, event, today, capture)]
+struct Rotations;
 
-struct Handler;
+#[group]
+#[commands(zqforecast, nickforecast, bonusforecast, countdown, ics)]
+struct Planning;
+
+#[group]
+#[commands(skill, ping, menu)]
+struct Tools;
+
+#[group]
+#[commands(lang, mylang, channellang, utc, myutc, timestamps, prefix, configrole, auditlog, rules)]
+struct Configuration;
+
+/// Every group of commands, in the order of the help.
+static GROUPS: [&CommandGroup; 4] = [&ROTATIONS_GROUP, &PLANNING_GROUP, &TOOLS_GROUP, &CONFIGURATION_GROUP];
+
+#[derive(Default)]
+struct Handler {
+    tasks_started: AtomicBool,
+}
 
 #[async_trait]
 impl EventHandler for Handler {
-    async fn ready(&self, _: Context, ready: Ready) {
-        println!("{}#{} is connected!", ready.user.name, ready.user.discriminator);
+    async fn ready(&self, ctx: Context, ready: Ready) {
+        info!(user = %ready.user.tag(), "Connected");
+        // ready fires again after every reconnect, the background tasks must only run once
+        if !self.tasks_started.swap(true, Ordering::SeqCst) {
+            commands::slash::register(&ctx).await;
+            tokio::spawn(commands::countdown::run_countdowns(ctx.clone()).instrument(info_span!("countdowns")));
+            tokio::spawn(commands::nicktracker::run_nicholas_reminders(ctx).instrument(info_span!("nicholas_reminders")));
+        }
+    }
+
+    async fn interaction_create(&self, ctx: Context, interaction: Interaction) {
+        commands::slash::handle(&ctx, interaction).await;
     }
 }
 
+/// The prefix configured for the guild, the default one in direct messages.
+#[hook]
+async fn guild_prefix(ctx: &Context, msg: &Message) -> Option<String> {
+    let guild = msg.guild_id.map(|guild| guild.0).unwrap_or(0);
+    let datas_lock = get_bot_datas(ctx).await;
+    let read_data = datas_lock.read().await;
+    Some(read_data.guilds_config.get_prefix(guild).to_string())
+}
+
+fn message_span(msg: &Message, command_name: &str) -> Span {
+    logging::command_span("prefix", msg.guild_id.map(|guild| guild.0), msg.channel_id.0, msg.author.id.0, command_name)
+}
+
+/// Tells the member in their language why a check refused the command.
+#[hook]
+async fn dispatch_error(ctx: &Context, msg: &Message, error: DispatchError, command_name: &str) {
+    info!(parent: &message_span(msg, command_name), outcome = "refused", reason = ?error, "Command refused");
+    if let DispatchError::CheckFailed(check, _) = error {
+        let refusal = {
+            let guild = msg.guild_id.map(|guild| guild.0).unwrap_or(0);
+            let datas_lock = get_bot_datas(ctx).await;
+            let read_data = datas_lock.read().await;
+            let lang = read_data.language(guild, msg.channel_id.0, msg.author.id.0);
+            let i18n_messages: &I18nMessageStore = read_data.i18n_messages.lng(lang).unwrap();
+            match check {
+                "ManageGuild" => i18n_messages.permission_manage_guild().to_string(),
+                _ => i18n_messages.permission_denied().to_string(),
+            }
+        };
+        if let Err(why) = msg.channel_id.say(&ctx.http, refusal).await {
+            error!(error = ?why, "Error sending message");
+        }
+    }
+}
+
+/// Refuses the commands turned off or rate limited, and starts tracing the others until `after`.
 #[hook]
 async fn before(ctx: &Context, msg: &Message, command_name: &str) -> bool {
-    println!("Got command '{}' by user '{}'", command_name, msg.author.name);
+    let span = message_span(msg, command_name);
+    let guild = msg.guild_id.map(|guild| guild.0).unwrap_or(0);
+    let refusal = {
+        let datas_lock = get_bot_datas(ctx).await;
+        let read_data = datas_lock.read().await;
+        let refusal = span.in_scope(|| {
+            match commands::rules::rule_refusal(&read_data, guild, msg.channel_id.0, msg.author.id.0, command_name) {
+                Some(refusal) => Err(("refused", Some(refusal))),
+                None => commands::ratelimit::rate_limit_refusal(&read_data, guild, msg.channel_id.0, msg.author.id.0, command_name)
+                    .map_err(|refusal| ("rate_limited", if refusal.first { Some(refusal.reply) } else { None })),
+            }
+        });
+        if refusal.is_ok() {
+            read_data.command_traces.start(msg.id.0, span.clone());
+        }
+        refusal
+    };
+    match refusal {
+        Ok(()) => true,
+        Err((outcome, refusal)) => {
+            info!(parent: &span, outcome, "Command refused");
+            if let Some(refusal) = refusal {
+                if let Err(why) = msg.channel_id.say(&ctx.http, refusal).await {
+                    error!(parent: &span, error = ?why, "Error sending message");
+                }
+            }
+            false // if `before` returns false, command processing doesn't happen.
+        }
+    }
+}
 
-    true // if `before` returns false, command processing doesn't happen.
+/// Traces how long the command took and whether it failed.
+#[hook]
+async fn after(ctx: &Context, msg: &Message, command_name: &str, result: CommandResult) {
+    let trace = {
+        let datas_lock = get_bot_datas(ctx).await;
+        let read_data = datas_lock.read().await;
+        read_data.command_traces.finish(msg.id.0)
+    };
+    let (span, latency) = trace.unwrap_or_else(|| (message_span(msg, command_name), Duration::default()));
+    let latency_ms = latency.as_millis() as u64;
+    match result {
+        Ok(()) => info!(parent: &span, latency_ms, outcome = "ok", "Command run"),
+        Err(why) => error!(parent: &span, latency_ms, outcome = "error", error = %why, "Command failed"),
+    }
 }
 
 #[tokio::main]
@@ -83,13 +226,31 @@ async fn main() {
     // let test_date = Utc.ymd(2020, 2, 29);
     // println!("{}", test_date);
     // exit(0);
+    let args: Vec<String> = env::args().skip(1).collect();
+    if args.first().map(String::as_str) == Some("ics") {
+        commands::ics::run_cli(&args[1..]);
+        return;
+    }
     dotenv().ok();
-    let framework = StandardFramework::new()
-        .configure(|c| c.prefix("-")) // set the bot's commands prefix to "~"
-        .group(&GENERAL_GROUP);
-
+    logging::init();
     // Login with a bot token from the environment
     let token = env::var("DISCORD_TOKEN").expect("token");
+    let bot_id = Http::new(&token).get_current_user().await.expect("Could not fetch the bot user").id;
+    // mentioning the bot always works, so a forgotten prefix can still be changed
+    let framework = StandardFramework::new()
+        .configure(|c| c
+            .prefix("")
+            .dynamic_prefix(guild_prefix)
+            .on_mention(Some(bot_id)))
+        .before(before)
+        .after(after)
+        .on_dispatch_error(dispatch_error)
+        .help(&HELP)
+        .group(&ROTATIONS_GROUP)
+        .group(&PLANNING_GROUP)
+        .group(&TOOLS_GROUP)
+        .group(&CONFIGURATION_GROUP);
+
     // println!("{}", token);
     let intents = GatewayIntents::GUILD_EMOJIS_AND_STICKERS
         | GatewayIntents::MESSAGE_CONTENT
@@ -98,21 +259,26 @@ async fn main() {
         | GatewayIntents::DIRECT_MESSAGE_REACTIONS
         | GatewayIntents::DIRECT_MESSAGES;
     let mut client = Client::builder(token, intents)
-        .event_handler(Handler)
+        .event_handler(Handler::default())
         .framework(framework)
         .await
         .expect("Error creating client");
     {
         // https://docs.rs/serenity/0.8.7/serenity/client/struct.Client.html#structfield.data
         let mut data = client.data.write().await;
-        let bot_datas = Arc::new(tokio::sync::RwLock::new(BotData::init()));
+        let database = Database::open(DATABASE_PATH).expect("Could not open the database");
+        GuildsConfig::import_csv(&database).expect("Could not import the guild settings");
+        UsersConfig::import_csv(&database).expect("Could not import the user settings");
+        LiveCountdownStore::import_csv(&database).expect("Could not import the countdowns");
+        NicholasTracker::import_csv(&database).expect("Could not import the Nicholas tracker");
+        let bot_datas = Arc::new(tokio::sync::RwLock::new(BotData::init(database)));
         data.insert::<BotData>(bot_datas);
     }
 
 
     // start listening for events by starting a single shard
     if let Err(why) = client.start().await {
-        println!("An error occurred while running the client: {:?}", why);
+        error!(error = ?why, "An error occurred while running the client");
     }
 }
 
@@ -133,19 +299,32 @@ pub struct BotData {
     pub zaishen_bounty: I18nStore<ZaishenQuestStore>,
     pub zaishen_mission: I18nStore<ZaishenQuestStore>,
     pub zaishen_combat: I18nStore<ZaishenQuestStore>,
+    pub vanguard_quest: I18nStore<ZaishenQuestStore>,
+    pub shining_blade_bounty: I18nStore<ZaishenQuestStore>,
     pub nicholas_traveler: I18nStore<NicholasGiftStore>,
+    pub pre_searing: I18nStore<PreSearingGiftStore>,
     pub bonus_pve: I18nStore<BonusEventStore>,
     pub bonus_pvp: I18nStore<BonusEventStore>,
     pub i18n_messages: I18nStore<I18nMessageStore>,
-    pub event: (Vec<SpecialEventPeriod>, I18nStore<SpecialEventStore>),
+    pub event: (SpecialEventCalendar, I18nStore<SpecialEventStore>),
+    pub event_bonus: EventBonusStore,
     pub guilds_config: GuildsConfig,
+    pub users_config: UsersConfig,
+    pub channels_config: ChannelsConfig,
+    pub command_rules: CommandRules,
+    pub rate_limiter: RateLimiter,
+    pub command_traces: CommandTraces,
+    pub bounty_bosses: BountyBossStore,
+    pub countdowns: LiveCountdownStore,
+    pub nicholas_tracker: NicholasTracker,
     pub skills: SKillI18nStore,
     pub attributes: I18nStore<AttributeStore>,
     pub professions: I18nStore<ProfessionStore>,
+    pub clock: Box<dyn Clock>,
 }
 
 impl BotData {
-    pub fn init() -> Self {
+    pub fn init(database: Database) -> Self {
         let special_events = {
             let mut m = HashMap::new();
             m.insert(Language::English, SpecialEventStore::from_csv("datas/special_events_en_US.csv"));
@@ -153,15 +332,19 @@ impl BotData {
             m
         };
 
-        let special_event_periods: Vec<SpecialEventPeriod> = {
-            get_special_events_time_range()
-        };
+        let special_event_calendar = SpecialEventCalendar::from_csv("datas/special_events_rules.csv", "datas/special_events.csv");
         let nicholas_traveler = {
             let mut m = HashMap::new();
             m.insert(Language::English, NicholasGiftStore::from_csv("datas/nicolas_traveler_en_US.csv"));
             m.insert(Language::French, NicholasGiftStore::from_csv("datas/nicolas_traveler_fr_FR.csv"));
             m
         };
+        let pre_searing = {
+            let mut m = HashMap::new();
+            m.insert(Language::English, PreSearingGiftStore::from_csv("datas/pre_searing_en_US.csv"));
+            m.insert(Language::French, PreSearingGiftStore::from_csv("datas/pre_searing_fr_FR.csv"));
+            m
+        };
         let bonus_pve_events = {
             let mut m = HashMap::new();
             m.insert(Language::English, BonusEventStore::from_csv("datas/bonus_pve_en_US.csv"));
@@ -198,6 +381,18 @@ impl BotData {
             m.insert(Language::French, ZaishenQuestStore::from_csv("datas/vz_fr_FR.csv"));
             m
         };
+        let vanguard_quests = {
+            let mut m = HashMap::new();
+            m.insert(Language::English, ZaishenQuestStore::from_csv("datas/vanguard_en_US.csv"));
+            m.insert(Language::French, ZaishenQuestStore::from_csv("datas/vanguard_fr_FR.csv"));
+            m
+        };
+        let shining_blade_bounties = {
+            let mut m = HashMap::new();
+            m.insert(Language::English, ZaishenQuestStore::from_csv("datas/wanted_en_US.csv"));
+            m.insert(Language::French, ZaishenQuestStore::from_csv("datas/wanted_fr_FR.csv"));
+            m
+        };
 
         let attributes = {
             let mut m = HashMap::new();
@@ -225,18 +420,48 @@ impl BotData {
             zaishen_bounty: I18nStore(zaishen_bounty_quests),
             zaishen_mission: I18nStore(zaishen_mission_quests),
             zaishen_combat: I18nStore(zaishen_combat_quests),
+            vanguard_quest: I18nStore(vanguard_quests),
+            shining_blade_bounty: I18nStore(shining_blade_bounties),
             nicholas_traveler: I18nStore(nicholas_traveler),
+            pre_searing: I18nStore(pre_searing),
             bonus_pve: I18nStore(bonus_pve_events),
             bonus_pvp: I18nStore(bonus_pvp_events),
             i18n_messages: I18nStore(i18n_messages),
-            event: (special_event_periods, I18nStore(special_events)),
-            guilds_config: GuildsConfig::load(),
+            event: (special_event_calendar, I18nStore(special_events)),
+            event_bonus: EventBonusStore::from_csv("datas/special_events_bonus.csv"),
+            guilds_config: GuildsConfig::load(database.clone()).expect("Could not read the guild settings"),
+            users_config: UsersConfig::load(database.clone()).expect("Could not read the user settings"),
+            channels_config: ChannelsConfig::load(database.clone()).expect("Could not read the channel settings"),
+            command_rules: CommandRules::load(database.clone()).expect("Could not read the command rules"),
+            rate_limiter: RateLimiter::parse(&env::var("RATE_LIMITS").unwrap_or_else(|_| DEFAULT_RATE_LIMITS.to_string())),
+            command_traces: CommandTraces::default(),
+            bounty_bosses: BountyBossStore::from_csv("datas/bz_bosses.csv"),
+            countdowns: LiveCountdownStore::load(database.clone()).expect("Could not read the countdowns"),
+            nicholas_tracker: NicholasTracker::load(database).expect("Could not read the Nicholas tracker"),
             skills: SKillI18nStore::new(),
             attributes: I18nStore(attributes),
             professions: I18nStore(professions),
+            clock: Box::new(SystemClock),
         }
         // Arc::new(tokio::sync::RwLock::new(datas))
     }
+
+    /// The user's own time zone wins over the one configured for the guild.
+    pub fn timezone(&self, guild: u64, user: u64) -> Tz {
+        self.users_config.get_timezone(user)
+            .unwrap_or_else(|| self.guilds_config.get_guild_config(guild).1)
+    }
+
+    /// The user's own language wins over the channel override, which wins over the guild language.
+    pub fn language(&self, guild: u64, channel: u64, user: u64) -> Language {
+        self.users_config.get_language(user)
+            .or_else(|| self.channels_config.get_language(channel))
+            .unwrap_or_else(|| self.guilds_config.get_guild_config(guild).0)
+    }
+
+    pub fn time_formatter(&self, guild: u64, user: u64) -> TimeFormatter {
+        TimeFormatter::new(self.timezone(guild, user), self.guilds_config.get_timestamp_style(guild))
+    }
 }
 
 impl TypeMapKey for BotData {
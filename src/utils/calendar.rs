@@ -0,0 +1,373 @@
+use std::collections::HashMap;
+use std::f64::consts::PI;
+use std::str::FromStr;
+
+use chrono::{Datelike, DateTime, Duration, NaiveDate, NaiveTime, TimeZone, Utc, Weekday};
+
+use crate::constants::{LUNAR_CALENDAR_OFFSET_HOURS, SPECIAL_EVENT_TIMEZONE};
+use crate::utils::{CSVFile, SpecialEventPeriod};
+use crate::utils::time::DateTimeRange;
+
+/// Day an event is anchored to within a given year.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum EventAnchor {
+    /// `MM-DD`
+    Fixed { month: u32, day: u32 },
+    /// `Sat>=03-11`, first given weekday on or after the date
+    WeekdayOnOrAfter { weekday: Weekday, month: u32, day: u32 },
+    /// `lastMon-05`, last given weekday of the month
+    LastWeekday { weekday: Weekday, month: u32 },
+    /// `lunar-new-year`, first day of the Chinese lunar calendar
+    LunarNewYear,
+    /// `easter`, Gregorian Easter Sunday
+    Easter,
+}
+
+fn parse_month_day(s: &str) -> Option<(u32, u32)> {
+    let (month, day) = s.split_once('-')?;
+    let month = u32::from_str(month).ok()?;
+    let day = u32::from_str(day).ok()?;
+    // 2020 is a leap year, so 02-29 is accepted
+    NaiveDate::from_ymd_opt(2020, month, day).map(|_| (month, day))
+}
+
+impl FromStr for EventAnchor {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || format!("Could not convert '{}' into an event anchor", s);
+        match s {
+            "lunar-new-year" => return Ok(EventAnchor::LunarNewYear),
+            "easter" => return Ok(EventAnchor::Easter),
+            _ => {}
+        }
+        if let Some(rest) = s.strip_prefix("last") {
+            let (weekday, month) = rest.split_once('-').ok_or_else(error)?;
+            let weekday = Weekday::from_str(weekday).map_err(|_| error())?;
+            let month = u32::from_str(month).ok().filter(|m| (1..=12).contains(m)).ok_or_else(error)?;
+            return Ok(EventAnchor::LastWeekday { weekday, month });
+        }
+        if let Some((weekday, date)) = s.split_once(">=") {
+            let weekday = Weekday::from_str(weekday).map_err(|_| error())?;
+            let (month, day) = parse_month_day(date).ok_or_else(error)?;
+            return Ok(EventAnchor::WeekdayOnOrAfter { weekday, month, day });
+        }
+        let (month, day) = parse_month_day(s).ok_or_else(error)?;
+        Ok(EventAnchor::Fixed { month, day })
+    }
+}
+
+impl EventAnchor {
+    /// `None` when the anchor doesn't exist that year (02-29 outside leap years).
+    pub fn date_in(&self, year: i32) -> Option<NaiveDate> {
+        match *self {
+            EventAnchor::Fixed { month, day } => NaiveDate::from_ymd_opt(year, month, day),
+            EventAnchor::WeekdayOnOrAfter { weekday, month, day } => {
+                let date = NaiveDate::from_ymd_opt(year, month, day)?;
+                let shift = (7 + weekday.num_days_from_monday() - date.weekday().num_days_from_monday()) % 7;
+                Some(date + Duration::days(shift as i64))
+            }
+            EventAnchor::LastWeekday { weekday, month } => {
+                let first_of_next = if month == 12 {
+                    NaiveDate::from_ymd_opt(year + 1, 1, 1)?
+                } else {
+                    NaiveDate::from_ymd_opt(year, month + 1, 1)?
+                };
+                let last = first_of_next.pred_opt()?;
+                let shift = (7 + last.weekday().num_days_from_monday() - weekday.num_days_from_monday()) % 7;
+                Some(last - Duration::days(shift as i64))
+            }
+            EventAnchor::LunarNewYear => Some(lunar_new_year(year)),
+            EventAnchor::Easter => Some(easter(year)),
+        }
+    }
+}
+
+/// A yearly special event: it starts `offset` days after its anchor at `time`
+/// (in the game's event time zone) and lasts `duration` days.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpecialEventRule {
+    pub id: u32,
+    pub anchor: EventAnchor,
+    pub offset: i64,
+    pub duration: i64,
+    pub time: NaiveTime,
+}
+
+impl SpecialEventRule {
+    pub fn occurrence(&self, year: i32) -> Option<SpecialEventPeriod> {
+        let start = self.anchor.date_in(year)? + Duration::days(self.offset);
+        let end = start + Duration::days(self.duration);
+        let range = DateTimeRange::new(event_instant(start, self.time), event_instant(end, self.time));
+        Some(SpecialEventPeriod(self.id, range))
+    }
+}
+
+fn event_instant(date: NaiveDate, time: NaiveTime) -> DateTime<Utc> {
+    let local = date.and_time(time);
+    SPECIAL_EVENT_TIMEZONE.from_local_datetime(&local)
+        .earliest()
+        // the local time falls in a DST gap, the clock reads one hour later
+        .unwrap_or_else(|| SPECIAL_EVENT_TIMEZONE.from_local_datetime(&(local + Duration::hours(1))).earliest().unwrap())
+        .with_timezone(&Utc)
+}
+
+/// Special events generated from yearly rules, with explicit dates taking over
+/// the generated occurrence of the same event and year.
+#[derive(Debug)]
+pub struct SpecialEventCalendar {
+    rules: Vec<SpecialEventRule>,
+    overrides: HashMap<(u32, i32), DateTimeRange<Utc>>,
+}
+
+impl SpecialEventCalendar {
+    pub fn new(rules: Vec<SpecialEventRule>, overrides: Vec<SpecialEventPeriod>) -> Self {
+        let overrides = overrides.into_iter()
+            .map(|period| ((period.0, event_year(&period.1.0)), period.1))
+            .collect();
+        Self { rules, overrides }
+    }
+
+    pub fn from_csv(rules_path: &str, overrides_path: &str) -> Self {
+        let csv = CSVFile::parse(rules_path).unwrap_or_else(|_| panic!("{} doesn't exist", rules_path));
+        let mut rules = vec![];
+        for x in csv.records {
+            let id = u32::from_str(x.first().unwrap()).unwrap();
+            let anchor = EventAnchor::from_str(x.get(1).unwrap()).unwrap();
+            let offset = i64::from_str(x.get(2).unwrap()).unwrap();
+            let duration = i64::from_str(x.get(3).unwrap()).unwrap();
+            let time = NaiveTime::parse_from_str(x.get(4).unwrap(), "%H:%M").unwrap();
+            rules.push(SpecialEventRule { id, anchor, offset, duration, time });
+        }
+        let csv = CSVFile::parse(overrides_path).unwrap_or_else(|_| panic!("{} doesn't exist", overrides_path));
+        let mut overrides = vec![];
+        for x in csv.records {
+            let id = u32::from_str(x.first().unwrap()).unwrap();
+            let start = DateTime::parse_from_rfc3339(x.get(1).unwrap()).unwrap().with_timezone(&Utc);
+            let end = DateTime::parse_from_rfc3339(x.get(2).unwrap()).unwrap().with_timezone(&Utc);
+            overrides.push(SpecialEventPeriod(id, DateTimeRange::new(start, end)));
+        }
+        Self::new(rules, overrides)
+    }
+
+    /// Every event starting in `year`, sorted by start.
+    pub fn periods_for_year(&self, year: i32) -> Vec<SpecialEventPeriod> {
+        let mut periods: Vec<SpecialEventPeriod> = self.rules.iter()
+            .filter(|rule| !self.overrides.contains_key(&(rule.id, year)))
+            .filter_map(|rule| rule.occurrence(year))
+            .collect();
+        periods.extend(self.overrides.iter()
+            .filter(|((_, override_year), _)| *override_year == year)
+            .map(|((id, _), range)| SpecialEventPeriod(*id, range.clone())));
+        periods.sort_by(|a, b| a.1.0.cmp(&b.1.0).then(a.0.cmp(&b.0)));
+        periods
+    }
+
+    /// Events of the previous, current and next year around `date`, enough to answer
+    /// running/next queries across new year.
+    pub fn periods_around(&self, date: &DateTime<Utc>) -> Vec<SpecialEventPeriod> {
+        let year = event_year(date);
+        ((year - 1)..=(year + 1))
+            .flat_map(|year| self.periods_for_year(year))
+            .collect()
+    }
+
+    /// Events running at `date`, those whose bonuses are in effect.
+    pub fn periods_running(&self, date: &DateTime<Utc>) -> Vec<SpecialEventPeriod> {
+        self.periods_around(date).into_iter()
+            .filter(|period| period.within(date))
+            .collect()
+    }
+
+    /// Events overlapping `[from, to)`, sorted by start.
+    pub fn periods_between(&self, from: &DateTime<Utc>, to: &DateTime<Utc>) -> Vec<SpecialEventPeriod> {
+        ((event_year(from) - 1)..=event_year(to))
+            .flat_map(|year| self.periods_for_year(year))
+            .filter(|period| period.1.1 > *from && period.1.0 < *to)
+            .collect()
+    }
+}
+
+fn event_year(date: &DateTime<Utc>) -> i32 {
+    date.with_timezone(&SPECIAL_EVENT_TIMEZONE).year()
+}
+
+/// Anonymous Gregorian algorithm.
+pub fn easter(year: i32) -> NaiveDate {
+    let a = year % 19;
+    let b = year / 100;
+    let c = year % 100;
+    let d = b / 4;
+    let e = b % 4;
+    let f = (b + 8) / 25;
+    let g = (b - f + 1) / 3;
+    let h = (19 * a + b - d - g + 15) % 30;
+    let i = c / 4;
+    let k = c % 4;
+    let l = (32 + 2 * e + 2 * i - h - k) % 7;
+    let m = (a + 11 * h + 22 * l) / 451;
+    let month = (h + l - 7 * m + 114) / 31;
+    let day = (h + l - 7 * m + 114) % 31 + 1;
+    NaiveDate::from_ymd_opt(year, month as u32, day as u32).unwrap()
+}
+
+/// The lunar new year is the day of the new moon, in China Standard Time, falling between
+/// January 21st and February 20th.
+pub fn lunar_new_year(year: i32) -> NaiveDate {
+    let earliest = NaiveDate::from_ymd_opt(year, 1, 21).unwrap();
+    let k = ((year - 2000) as f64 * 12.3685).floor() as i64;
+    (k - 1..k + 4)
+        .map(|k| (new_moon(k) + Duration::hours(LUNAR_CALENDAR_OFFSET_HOURS)).date_naive())
+        .find(|date| *date >= earliest)
+        .unwrap()
+}
+
+/// Instant of the `k`-th new moon since January 2000, after Meeus' Astronomical Algorithms
+/// (chapter 49, planetary corrections left out), accurate to a few minutes.
+fn new_moon(k: i64) -> DateTime<Utc> {
+    let k = k as f64;
+    let t = k / 1236.85;
+    let (t2, t3, t4) = (t * t, t * t * t, t * t * t * t);
+    let jde = 2451550.09766 + 29.530588861 * k + 0.00015437 * t2 - 0.000000150 * t3 + 0.00000000073 * t4;
+    let e = 1.0 - 0.002516 * t - 0.0000074 * t2;
+    let rad = |deg: f64| (deg % 360.0) * PI / 180.0;
+    let m = rad(2.5534 + 29.10535670 * k - 0.0000014 * t2 - 0.00000011 * t3);
+    let mp = rad(201.5643 + 385.81693528 * k + 0.0107582 * t2 + 0.00001238 * t3 - 0.000000058 * t4);
+    let f = rad(160.7108 + 390.67050284 * k - 0.0016118 * t2 - 0.00000227 * t3 + 0.000000011 * t4);
+    let omega = rad(124.7746 - 1.56375588 * k + 0.0020672 * t2 + 0.00000215 * t3);
+    let correction = -0.40720 * mp.sin()
+        + 0.17241 * e * m.sin()
+        + 0.01608 * (2.0 * mp).sin()
+        + 0.01039 * (2.0 * f).sin()
+        + 0.00739 * e * (mp - m).sin()
+        - 0.00514 * e * (mp + m).sin()
+        + 0.00208 * e * e * (2.0 * m).sin()
+        - 0.00111 * (mp - 2.0 * f).sin()
+        - 0.00057 * (mp + 2.0 * f).sin()
+        + 0.00056 * e * (2.0 * mp + m).sin()
+        - 0.00042 * (3.0 * mp).sin()
+        + 0.00042 * e * (m + 2.0 * f).sin()
+        + 0.00038 * e * (m - 2.0 * f).sin()
+        - 0.00024 * e * (2.0 * mp - m).sin()
+        - 0.00017 * omega.sin()
+        - 0.00007 * (mp + 2.0 * m).sin()
+        + 0.00004 * (2.0 * mp - 2.0 * f).sin()
+        + 0.00004 * (3.0 * m).sin()
+        + 0.00003 * (mp + m - 2.0 * f).sin()
+        + 0.00003 * (2.0 * mp + 2.0 * f).sin()
+        - 0.00003 * (mp + m + 2.0 * f).sin()
+        + 0.00003 * (mp - m + 2.0 * f).sin()
+        - 0.00002 * (mp - m - 2.0 * f).sin()
+        - 0.00002 * (3.0 * mp + m).sin()
+        + 0.00002 * (4.0 * mp).sin();
+    // Julian day 2440587.5 is the unix epoch
+    let seconds = ((jde + correction - 2440587.5) * 86400.0).round() as i64;
+    Utc.timestamp_opt(seconds, 0).unwrap()
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use chrono::{Datelike, NaiveDate, NaiveTime, TimeZone, Utc, Weekday};
+
+    use crate::utils::calendar::{easter, EventAnchor, lunar_new_year, SpecialEventCalendar, SpecialEventRule};
+    use crate::utils::SpecialEventPeriod;
+    use crate::utils::time::DateTimeRange;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    pub fn lunar_new_year_dates() {
+        let known = [
+            date(2019, 2, 5), date(2020, 1, 25), date(2021, 2, 12), date(2022, 2, 1),
+            date(2023, 1, 22), date(2024, 2, 10), date(2025, 1, 29), date(2026, 2, 17),
+            date(2027, 2, 6), date(2028, 1, 26), date(2029, 2, 13), date(2030, 2, 3),
+        ];
+        for expected in known.iter() {
+            assert_eq!(lunar_new_year(expected.year()), *expected);
+        }
+    }
+
+    #[test]
+    pub fn easter_dates() {
+        assert_eq!(easter(2019), date(2019, 4, 21));
+        assert_eq!(easter(2020), date(2020, 4, 12));
+        assert_eq!(easter(2024), date(2024, 3, 31));
+        assert_eq!(easter(2038), date(2038, 4, 25));
+    }
+
+    #[test]
+    pub fn parse_anchors() {
+        assert_eq!(EventAnchor::from_str("12-19"), Ok(EventAnchor::Fixed { month: 12, day: 19 }));
+        assert_eq!(EventAnchor::from_str("Sat>=03-11"), Ok(EventAnchor::WeekdayOnOrAfter { weekday: Weekday::Sat, month: 3, day: 11 }));
+        assert_eq!(EventAnchor::from_str("lastMon-05"), Ok(EventAnchor::LastWeekday { weekday: Weekday::Mon, month: 5 }));
+        assert_eq!(EventAnchor::from_str("lunar-new-year"), Ok(EventAnchor::LunarNewYear));
+        assert!(EventAnchor::from_str("13-01").is_err());
+        assert!(EventAnchor::from_str("Foo>=03-11").is_err());
+    }
+
+    #[test]
+    pub fn weekday_anchors() {
+        let thanksgiving = EventAnchor::from_str("Thu>=11-22").unwrap();
+        assert_eq!(thanksgiving.date_in(2020), Some(date(2020, 11, 26)));
+        assert_eq!(thanksgiving.date_in(2022), Some(date(2022, 11, 24)));
+        let memorial = EventAnchor::from_str("lastMon-05").unwrap();
+        assert_eq!(memorial.date_in(2021), Some(date(2021, 5, 31)));
+        let new_year_eve = EventAnchor::from_str("lastFri-12").unwrap();
+        assert_eq!(new_year_eve.date_in(2021), Some(date(2021, 12, 31)));
+        let leap = EventAnchor::from_str("02-29").unwrap();
+        assert_eq!(leap.date_in(2021), None);
+    }
+
+    fn rule(id: u32, anchor: &str, offset: i64, duration: i64) -> SpecialEventRule {
+        SpecialEventRule {
+            id,
+            anchor: EventAnchor::from_str(anchor).unwrap(),
+            offset,
+            duration,
+            time: NaiveTime::from_hms_opt(12, 0, 0).unwrap(),
+        }
+    }
+
+    #[test]
+    pub fn occurrences_follow_pacific_daylight_saving() {
+        let wintersday = rule(12, "12-19", 0, 14).occurrence(2020).unwrap();
+        assert_eq!(wintersday.1.0, Utc.with_ymd_and_hms(2020, 12, 19, 20, 0, 0).unwrap());
+        assert_eq!(wintersday.1.1, Utc.with_ymd_and_hms(2021, 1, 2, 20, 0, 0).unwrap());
+        let dragon_festival = rule(5, "06-27", 0, 7).occurrence(2021).unwrap();
+        assert_eq!(dragon_festival.1.0, Utc.with_ymd_and_hms(2021, 6, 27, 19, 0, 0).unwrap());
+        let special_treats = rule(11, "Thu>=11-22", -5, 7).occurrence(2020).unwrap();
+        assert_eq!(special_treats.1.0, Utc.with_ymd_and_hms(2020, 11, 21, 20, 0, 0).unwrap());
+    }
+
+    #[test]
+    pub fn overrides_replace_generated_occurrence() {
+        let start = Utc.with_ymd_and_hms(2020, 1, 31, 20, 0, 0).unwrap();
+        let end = Utc.with_ymd_and_hms(2020, 2, 7, 20, 0, 0).unwrap();
+        let calendar = SpecialEventCalendar::new(
+            vec![rule(0, "lunar-new-year", 0, 7), rule(12, "12-19", 0, 14)],
+            vec![SpecialEventPeriod(0, DateTimeRange::new(start, end))],
+        );
+        let periods = calendar.periods_for_year(2020);
+        assert_eq!(periods.len(), 2);
+        assert_eq!(periods[0].0, 0);
+        assert_eq!(periods[0].1.0, start);
+        let periods = calendar.periods_for_year(2021);
+        assert_eq!(periods[0].1.0, Utc.with_ymd_and_hms(2021, 2, 12, 20, 0, 0).unwrap());
+    }
+
+    #[test]
+    pub fn running_event_across_new_year() {
+        let calendar = SpecialEventCalendar::new(vec![rule(12, "12-19", 0, 14)], vec![]);
+        let now = Utc.with_ymd_and_hms(2031, 1, 1, 0, 0, 0).unwrap();
+        let running = calendar.periods_running(&now);
+        assert_eq!(running.len(), 1);
+        assert_eq!(running[0].1.0, Utc.with_ymd_and_hms(2030, 12, 19, 20, 0, 0).unwrap());
+        let between = calendar.periods_between(&now, &Utc.with_ymd_and_hms(2031, 12, 31, 0, 0, 0).unwrap());
+        assert_eq!(between.len(), 2);
+    }
+
+}
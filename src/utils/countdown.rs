@@ -0,0 +1,170 @@
+use std::str::FromStr;
+
+use chrono::{DateTime, Duration, Utc};
+use rusqlite::{Connection, params};
+
+use crate::enums::Language;
+use crate::utils::{CSVRecord, import_legacy_csv};
+use crate::utils::database::Database;
+
+const LEGACY_COUNTDOWNS_PATH: &str = "countdowns.csv";
+
+/// A message the bot keeps editing until `target` is reached.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LiveCountdown {
+    pub channel: u64,
+    pub message: u64,
+    pub target: DateTime<Utc>,
+    pub language: Language,
+    pub label: String,
+    /// Not persisted, so every tracked message gets refreshed right after a restart.
+    pub next_edit: DateTime<Utc>,
+}
+
+impl LiveCountdown {
+    pub fn new(channel: u64, message: u64, target: DateTime<Utc>, language: Language, label: String) -> Self {
+        Self { channel, message, target, language, label, next_edit: DateTime::<Utc>::MIN_UTC }
+    }
+
+    fn from_record(record: &CSVRecord) -> Option<Self> {
+        let channel = u64::from_str(record.first()?).ok()?;
+        let message = u64::from_str(record.get(1)?).ok()?;
+        let target = DateTime::parse_from_rfc3339(record.get(2)?).ok()?.with_timezone(&Utc);
+        let language = record.get(3)?.parse::<Language>().ok()?;
+        let label = record.get(4).cloned().unwrap_or_default();
+        Some(Self::new(channel, message, target, language, label))
+    }
+
+    fn insert(&self, connection: &Connection) -> rusqlite::Result<usize> {
+        connection.execute(
+            "INSERT OR IGNORE INTO live_countdown (message, channel, target, language, label) VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![self.message, self.channel, self.target.to_rfc3339(), self.language.to_string(), self.label],
+        )
+    }
+
+    /// When the message should be edited next after an edit at `now`: every minute,
+    /// then every second during the last minute.
+    pub fn schedule_after(&self, now: DateTime<Utc>) -> DateTime<Utc> {
+        let last_minute = self.target - Duration::minutes(1);
+        if now >= last_minute {
+            (now + Duration::seconds(1)).min(self.target)
+        } else {
+            (now + Duration::minutes(1)).min(last_minute)
+        }
+    }
+}
+
+/// Tracked countdown messages, kept in memory and written through to the database.
+#[derive(Debug)]
+pub struct LiveCountdownStore {
+    database: Database,
+    countdowns: Vec<LiveCountdown>,
+}
+
+impl LiveCountdownStore {
+    pub fn load(database: Database) -> rusqlite::Result<Self> {
+        let countdowns = database.connection()
+            .prepare("SELECT channel, message, target, language, label FROM live_countdown")?
+            .query_map([], |row| {
+                let (channel, message, label) = (row.get(0)?, row.get(1)?, row.get(4)?);
+                let target: String = row.get(2)?;
+                let language: String = row.get(3)?;
+                let language = language.parse().unwrap_or(Language::English);
+                Ok(DateTime::parse_from_rfc3339(&target).ok()
+                    .map(|target| LiveCountdown::new(channel, message, target.with_timezone(&Utc), language, label)))
+            })?
+            .filter_map(Result::transpose)
+            .collect::<rusqlite::Result<_>>()?;
+        Ok(Self { database, countdowns })
+    }
+
+    /// Same as [`crate::utils::GuildsConfig::import_csv`] for the countdowns' file.
+    pub fn import_csv(database: &Database) -> rusqlite::Result<()> {
+        import_legacy_csv(database, LEGACY_COUNTDOWNS_PATH, |transaction, record| {
+            LiveCountdown::from_record(record)
+                .map(|countdown| countdown.insert(transaction))
+                .transpose()
+        })
+    }
+
+    pub fn add(&mut self, countdown: LiveCountdown) -> rusqlite::Result<()> {
+        countdown.insert(&self.database.connection())?;
+        self.countdowns.push(countdown);
+        Ok(())
+    }
+
+    pub fn remove(&mut self, message: u64) -> rusqlite::Result<()> {
+        self.database.connection().execute("DELETE FROM live_countdown WHERE message = ?1", params![message])?;
+        self.countdowns.retain(|countdown| countdown.message != message);
+        Ok(())
+    }
+
+    /// Whether a message has to be edited at `now`.
+    pub fn has_due(&self, now: DateTime<Utc>) -> bool {
+        self.countdowns.iter().any(|countdown| countdown.next_edit <= now)
+    }
+
+    /// Countdowns whose message has to be edited at `now`, already rescheduled for their next edit.
+    pub fn take_due(&mut self, now: DateTime<Utc>) -> Vec<LiveCountdown> {
+        self.countdowns.iter_mut()
+            .filter(|countdown| countdown.next_edit <= now)
+            .map(|countdown| {
+                countdown.next_edit = countdown.schedule_after(now);
+                countdown.clone()
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::{DateTime, Duration, TimeZone, Utc};
+
+    use crate::enums::Language;
+    use crate::utils::countdown::{LiveCountdown, LiveCountdownStore};
+    use crate::utils::database::Database;
+
+    fn utc(h: u32, min: u32, s: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2020, 9, 7, h, min, s).unwrap()
+    }
+
+    #[test]
+    pub fn edits_every_minute_then_every_second() {
+        let countdown = LiveCountdown::new(1, 2, utc(16, 0, 0), Language::English, "Daily reset:".to_string());
+        assert_eq!(countdown.schedule_after(utc(15, 0, 0)), utc(15, 1, 0));
+        assert_eq!(countdown.schedule_after(utc(15, 58, 30)), utc(15, 59, 0));
+        assert_eq!(countdown.schedule_after(utc(15, 59, 0)), utc(15, 59, 1));
+        assert_eq!(countdown.schedule_after(utc(15, 59, 59)), utc(16, 0, 0));
+        assert_eq!(countdown.schedule_after(utc(16, 0, 0)), utc(16, 0, 0));
+    }
+
+    #[test]
+    pub fn due_countdowns_are_rescheduled() {
+        let target = utc(16, 0, 0);
+        let mut store = LiveCountdownStore::load(Database::open_in_memory().unwrap()).unwrap();
+        store.add(LiveCountdown::new(1, 2, target, Language::French, "Raid".to_string())).unwrap();
+        store.add(LiveCountdown::new(1, 3, target + Duration::hours(1), Language::English, "Event".to_string())).unwrap();
+        assert!(store.has_due(utc(15, 0, 0)));
+        assert_eq!(store.take_due(utc(15, 0, 0)).len(), 2);
+        assert!(!store.has_due(utc(15, 0, 30)));
+        assert!(store.take_due(utc(15, 0, 30)).is_empty());
+        let due = store.take_due(utc(15, 1, 0));
+        assert_eq!(due.len(), 2);
+        assert_eq!(due[0].next_edit, utc(15, 2, 0));
+    }
+
+    #[test]
+    pub fn countdowns_persisted() {
+        let database = Database::open_in_memory().unwrap();
+        let legacy = ["1", "2", "2020-09-07T16:00:00+00:00", "fr-FR", "Raid"].iter().map(|field| field.to_string()).collect();
+        let imported = LiveCountdown::from_record(&legacy).unwrap();
+        assert_eq!(imported.insert(&database.connection()).unwrap(), 1);
+        assert_eq!(imported.insert(&database.connection()).unwrap(), 0);
+        let mut store = LiveCountdownStore::load(database.clone()).unwrap();
+        assert_eq!(store.countdowns, vec![imported]);
+        store.add(LiveCountdown::new(1, 3, utc(17, 0, 0), Language::English, "Event;\nbring cons".to_string())).unwrap();
+        store.remove(2).unwrap();
+        let store = LiveCountdownStore::load(database).unwrap();
+        assert_eq!(store.countdowns, vec![LiveCountdown::new(1, 3, utc(17, 0, 0), Language::English, "Event;\nbring cons".to_string())]);
+    }
+}
@@ -0,0 +1,136 @@
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+use rusqlite::Connection;
+
+pub const DATABASE_PATH: &str = "gw-bot.db";
+
+/// Schema changes, applied in order. `PRAGMA user_version` holds how many were applied,
+/// so a migration must never be edited once released, only followed by a new one.
+const MIGRATIONS: &[&str] = &[
+    "CREATE TABLE guild_config (
+        guild INTEGER PRIMARY KEY,
+        language TEXT NOT NULL,
+        timezone TEXT NOT NULL,
+        timestamps TEXT NOT NULL,
+        prefix TEXT NOT NULL
+    );
+    CREATE TABLE user_config (
+        user INTEGER PRIMARY KEY,
+        timezone TEXT
+    );",
+    // languages were saved as their English name before being saved as locale codes
+    "UPDATE guild_config SET language = CASE lower(language)
+        WHEN 'english' THEN 'en-US'
+        WHEN 'french' THEN 'fr-FR'
+        ELSE language
+    END;",
+    "ALTER TABLE user_config ADD COLUMN language TEXT;
+    CREATE TABLE channel_config (
+        channel INTEGER PRIMARY KEY,
+        guild INTEGER NOT NULL,
+        language TEXT
+    );",
+    "ALTER TABLE guild_config ADD COLUMN audit_channel INTEGER;
+    CREATE TABLE guild_config_role (
+        guild INTEGER NOT NULL,
+        role INTEGER NOT NULL,
+        PRIMARY KEY (guild, role)
+    );",
+    "CREATE TABLE command_disabled (
+        guild INTEGER NOT NULL,
+        target TEXT NOT NULL,
+        PRIMARY KEY (guild, target)
+    );
+    CREATE TABLE command_channel (
+        guild INTEGER NOT NULL,
+        target TEXT NOT NULL,
+        channel INTEGER NOT NULL,
+        PRIMARY KEY (guild, target, channel)
+    );",
+    "CREATE TABLE nicholas_tracker (
+        user INTEGER PRIMARY KEY,
+        reminder INTEGER NOT NULL,
+        language TEXT NOT NULL,
+        reminded INTEGER
+    );
+    CREATE TABLE nicholas_week (
+        user INTEGER NOT NULL,
+        week INTEGER NOT NULL,
+        PRIMARY KEY (user, week)
+    );
+    CREATE TABLE live_countdown (
+        message INTEGER PRIMARY KEY,
+        channel INTEGER NOT NULL,
+        target TEXT NOT NULL,
+        language TEXT NOT NULL,
+        label TEXT NOT NULL
+    );",
+];
+
+/// Shared handle on the SQLite database holding the persistent state of the bot.
+#[derive(Debug, Clone)]
+pub struct Database(Arc<Mutex<Connection>>);
+
+impl Database {
+    pub fn open(path: &str) -> rusqlite::Result<Self> {
+        Self::migrated(Connection::open(path)?)
+    }
+
+    /// Nothing is written to disk, for the command line tools and the tests.
+    pub fn open_in_memory() -> rusqlite::Result<Self> {
+        Self::migrated(Connection::open_in_memory()?)
+    }
+
+    fn migrated(mut connection: Connection) -> rusqlite::Result<Self> {
+        migrate(&mut connection)?;
+        Ok(Self(Arc::new(Mutex::new(connection))))
+    }
+
+    pub fn connection(&self) -> MutexGuard<'_, Connection> {
+        // a panic while holding the lock can't leave a half written change, SQLite rolls it back
+        self.0.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// Applies the migrations missing from the database, each one in its own transaction.
+fn migrate(connection: &mut Connection) -> rusqlite::Result<()> {
+    let version: usize = connection.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+    for (index, migration) in MIGRATIONS.iter().enumerate().skip(version) {
+        let transaction = connection.transaction()?;
+        transaction.execute_batch(migration)?;
+        transaction.pragma_update(None, "user_version", index + 1)?;
+        transaction.commit()?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use rusqlite::Connection;
+
+    use crate::utils::database::{migrate, MIGRATIONS};
+
+    #[test]
+    pub fn migrations_run_once() {
+        let mut connection = Connection::open_in_memory().unwrap();
+        migrate(&mut connection).unwrap();
+        migrate(&mut connection).unwrap();
+        let version: usize = connection.query_row("PRAGMA user_version", [], |row| row.get(0)).unwrap();
+        assert_eq!(version, MIGRATIONS.len());
+        connection.execute("INSERT INTO user_config (user, timezone) VALUES (1, 'Europe/Paris')", []).unwrap();
+    }
+
+    #[test]
+    pub fn language_names_migrated_to_codes() {
+        let mut connection = Connection::open_in_memory().unwrap();
+        connection.execute_batch(MIGRATIONS[0]).unwrap();
+        connection.pragma_update(None, "user_version", 1).unwrap();
+        connection.execute_batch("INSERT INTO guild_config VALUES (1, 'French', 'UTC', 'discord', '-');
+            INSERT INTO guild_config VALUES (2, 'English', 'UTC', 'discord', '-');").unwrap();
+        migrate(&mut connection).unwrap();
+        let languages: Vec<String> = connection.prepare("SELECT language FROM guild_config ORDER BY guild").unwrap()
+            .query_map([], |row| row.get(0)).unwrap()
+            .collect::<rusqlite::Result<_>>().unwrap();
+        assert_eq!(languages, vec!["fr-FR", "en-US"]);
+    }
+}
@@ -0,0 +1,237 @@
+use std::fmt;
+use std::fmt::{Display, Formatter, Write};
+use std::str::FromStr;
+
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+
+use crate::enums::Language;
+use crate::utils::reset::ResetCycle;
+
+const ICS_DATETIME_FORMAT: &str = "%Y%m%dT%H%M%SZ";
+const ICS_LINE_LIMIT: usize = 75;
+pub const ICS_DEFAULT_DAYS: i64 = 30;
+pub const ICS_MAX_DAYS: i64 = 366;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum IcsFeed {
+    Zaishen,
+    Vanguard,
+    Wanted,
+    Nicholas,
+    PreSearing,
+    Bonus,
+    Event,
+}
+
+impl IcsFeed {
+    pub const ALL: [IcsFeed; 7] = [
+        IcsFeed::Zaishen,
+        IcsFeed::Vanguard,
+        IcsFeed::Wanted,
+        IcsFeed::Nicholas,
+        IcsFeed::PreSearing,
+        IcsFeed::Bonus,
+        IcsFeed::Event,
+    ];
+}
+
+impl FromStr for IcsFeed {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "zq" | "zaishen" => Ok(IcsFeed::Zaishen),
+            "vq" | "vanguard" => Ok(IcsFeed::Vanguard),
+            "wanted" | "sb" => Ok(IcsFeed::Wanted),
+            "nick" | "nicholas" => Ok(IcsFeed::Nicholas),
+            "pre" | "presearing" => Ok(IcsFeed::PreSearing),
+            "bonus" | "bonuses" => Ok(IcsFeed::Bonus),
+            "event" | "events" => Ok(IcsFeed::Event),
+            _ => Err(format!("Could not convert '{}' into a feed (zq, vq, wanted, nick, pre, bonus, event)", s))
+        }
+    }
+}
+
+/// Why an export request was refused, the Discord command telling it in the reader's language.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IcsError {
+    /// A token that is neither a date, a language nor a feed.
+    Feed(String),
+    /// More than a start and an end date.
+    Dates,
+    /// The end isn't after the start or is more than `ICS_MAX_DAYS` away.
+    Range,
+}
+
+impl Display for IcsError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            IcsError::Feed(feed) => write!(f, "Could not convert '{}' into a feed (zq, vq, wanted, nick, pre, bonus, event)", feed),
+            IcsError::Dates => write!(f, "Expected at most two dates formatted as YYYY-MM-DD"),
+            IcsError::Range => write!(f, "The end date must be after the start date and within {} days", ICS_MAX_DAYS),
+        }
+    }
+}
+
+/// What to export: `[from date] [to date] [language] [feeds...]` in any order, the first date being the start.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IcsRequest {
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    pub language: Language,
+    pub feeds: Vec<IcsFeed>,
+}
+
+impl IcsRequest {
+    pub fn parse<'a>(tokens: impl IntoIterator<Item=&'a str>, language: Language, now: DateTime<Utc>) -> Result<Self, IcsError> {
+        let mut dates = vec![];
+        let mut language = language;
+        let mut feeds = vec![];
+        for token in tokens {
+            if let Ok(date) = token.parse::<NaiveDate>() {
+                dates.push(date);
+            } else if let Ok(lang) = token.parse::<Language>() {
+                language = lang;
+            } else {
+                for feed in token.split(',').filter(|feed| !feed.is_empty()) {
+                    let feed = feed.parse::<IcsFeed>().map_err(|_| IcsError::Feed(feed.to_string()))?;
+                    if !feeds.contains(&feed) {
+                        feeds.push(feed);
+                    }
+                }
+            }
+        }
+        if dates.len() > 2 {
+            return Err(IcsError::Dates);
+        }
+        let from = match dates.first() {
+            Some(date) => ResetCycle::Daily.reset_of(*date),
+            None => ResetCycle::Daily.previous(now),
+        };
+        let to = match dates.get(1) {
+            Some(date) => ResetCycle::Daily.reset_of(*date),
+            None => from + Duration::days(ICS_DEFAULT_DAYS),
+        };
+        if to <= from || to - from > Duration::days(ICS_MAX_DAYS) {
+            return Err(IcsError::Range);
+        }
+        if feeds.is_empty() {
+            feeds = IcsFeed::ALL.to_vec();
+        }
+        Ok(Self { from, to, language, feeds })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IcsEvent {
+    pub uid: String,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    pub summary: String,
+    pub description: String,
+}
+
+/// Serializes the events as an RFC 5545 calendar, `stamp` being the generation instant.
+pub fn write_calendar(events: &[IcsEvent], stamp: DateTime<Utc>) -> String {
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//GWBot//Guild Wars rotations//EN".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+    ];
+    for event in events {
+        lines.push("BEGIN:VEVENT".to_string());
+        lines.push(format!("UID:{}", event.uid));
+        lines.push(format!("DTSTAMP:{}", stamp.format(ICS_DATETIME_FORMAT)));
+        lines.push(format!("DTSTART:{}", event.start.format(ICS_DATETIME_FORMAT)));
+        lines.push(format!("DTEND:{}", event.end.format(ICS_DATETIME_FORMAT)));
+        lines.push(format!("SUMMARY:{}", escape_text(&event.summary)));
+        if !event.description.is_empty() {
+            lines.push(format!("DESCRIPTION:{}", escape_text(&event.description)));
+        }
+        lines.push("END:VEVENT".to_string());
+    }
+    lines.push("END:VCALENDAR".to_string());
+    lines.iter().fold(String::new(), |mut calendar, line| {
+        let _ = write!(calendar, "{}\r\n", fold_line(line));
+        calendar
+    })
+}
+
+fn escape_text(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+/// Splits lines longer than 75 octets, continuation lines starting with a space.
+fn fold_line(line: &str) -> String {
+    let mut folded = String::new();
+    let mut length = 0;
+    for c in line.chars() {
+        if length + c.len_utf8() > ICS_LINE_LIMIT {
+            folded.push_str("\r\n ");
+            length = 1;
+        }
+        folded.push(c);
+        length += c.len_utf8();
+    }
+    folded
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::{TimeZone, Utc};
+
+    use crate::enums::Language;
+    use crate::utils::ics::{fold_line, IcsError, IcsEvent, IcsFeed, IcsRequest, write_calendar};
+
+    #[test]
+    pub fn parse_request() {
+        let now = Utc.with_ymd_and_hms(2020, 9, 7, 12, 0, 0).unwrap();
+        let default = IcsRequest::parse(vec![], Language::English, now).unwrap();
+        assert_eq!(default.from, Utc.with_ymd_and_hms(2020, 9, 6, 16, 0, 0).unwrap());
+        assert_eq!(default.to, Utc.with_ymd_and_hms(2020, 10, 6, 16, 0, 0).unwrap());
+        assert_eq!(default.feeds, IcsFeed::ALL.to_vec());
+        let request = IcsRequest::parse(vec!["fr", "2020-10-01", "zq,event", "2020-10-08", "zq"], Language::English, now).unwrap();
+        assert_eq!(request.language, Language::French);
+        assert_eq!(request.from, Utc.with_ymd_and_hms(2020, 10, 1, 16, 0, 0).unwrap());
+        assert_eq!(request.to, Utc.with_ymd_and_hms(2020, 10, 8, 16, 0, 0).unwrap());
+        assert_eq!(request.feeds, vec![IcsFeed::Zaishen, IcsFeed::Event]);
+        assert_eq!(IcsRequest::parse(vec!["2020-10-08", "2020-10-01"], Language::English, now), Err(IcsError::Range));
+        assert_eq!(IcsRequest::parse(vec!["2020-01-01", "2022-01-01"], Language::English, now), Err(IcsError::Range));
+        assert_eq!(IcsRequest::parse(vec!["2020-10-08", "2020-10-01", "2020-10-15"], Language::English, now), Err(IcsError::Dates));
+        let request = IcsRequest::parse(vec!["vq,wanted", "pre"], Language::English, now).unwrap();
+        assert_eq!(request.feeds, vec![IcsFeed::Vanguard, IcsFeed::Wanted, IcsFeed::PreSearing]);
+        assert_eq!(IcsRequest::parse(vec!["pvp"], Language::English, now), Err(IcsError::Feed("pvp".to_string())));
+    }
+
+    #[test]
+    pub fn calendar_layout() {
+        let stamp = Utc.with_ymd_and_hms(2020, 9, 7, 12, 0, 0).unwrap();
+        let event = IcsEvent {
+            uid: "nick-20200907@gwbot".to_string(),
+            start: Utc.with_ymd_and_hms(2020, 9, 7, 15, 0, 0).unwrap(),
+            end: Utc.with_ymd_and_hms(2020, 9, 14, 15, 0, 0).unwrap(),
+            summary: "Drake Kabob; 5, Dragon's Lair".to_string(),
+            description: "line\nnext".to_string(),
+        };
+        let calendar = write_calendar(&[event], stamp);
+        assert!(calendar.starts_with("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n"));
+        assert!(calendar.ends_with("END:VEVENT\r\nEND:VCALENDAR\r\n"));
+        assert!(calendar.contains("\r\nDTSTART:20200907T150000Z\r\nDTEND:20200914T150000Z\r\n"));
+        assert!(calendar.contains("\r\nSUMMARY:Drake Kabob\\; 5\\, Dragon's Lair\r\n"));
+        assert!(calendar.contains("\r\nDESCRIPTION:line\\nnext\r\n"));
+    }
+
+    #[test]
+    pub fn long_lines_are_folded() {
+        let line = format!("SUMMARY:{}", "é".repeat(50));
+        let folded = fold_line(&line);
+        for part in folded.split("\r\n") {
+            assert!(part.len() <= 75);
+        }
+        assert_eq!(folded.replace("\r\n ", ""), line);
+    }
+}
@@ -1,13 +1,27 @@
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
 use std::fs::File;
-use std::io::{BufRead, BufReader, Lines, Write};
+use std::io::{BufRead, BufReader, Lines};
 use std::str::FromStr;
 
-use chrono::{DateTime, TimeZone, Utc};
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
+use rusqlite::{params, params_from_iter, Row, ToSql, Transaction};
+use tracing::{error, info, warn};
 
+use crate::constants::DEFAULT_PREFIX;
 use crate::enums::{AttributeType, Language, ProfessionType};
-use crate::utils::time::{DateTimeRange, DateTimeRangeComparison};
+use crate::utils::database::Database;
+use crate::utils::time::{DateTimeRange, DateTimeRangeComparison, parse_timezone, TimestampStyle};
 
+pub mod calendar;
+pub mod countdown;
+pub mod database;
+pub mod ics;
+pub mod nicholas;
+pub mod ratelimit;
+pub mod reset;
+pub mod rules;
 pub mod skill;
 pub mod time;
 
@@ -36,22 +50,6 @@ impl CSVFile {
         self.records.push(record);
     }
 
-    fn save(path: &str, headers: Vec<String>, records: Vec<CSVRecord>) {
-        let file = File::create(path).or_else(|_| File::open(path));
-        file.and_then(|mut f| {
-            let headers = format!("{}\n", headers.join(";"));
-            f.write_all(headers.as_bytes()).ok();
-            records.iter()
-                .map(|record| record.join(";"))
-                .map(|record| format!("{}\n", record))
-                .for_each(|record| {
-                    f.write_all(record.as_bytes()).ok();
-                });
-
-            Ok(())
-        }).ok();
-    }
-
     fn parse(path: &str) -> Result<Self, ()> {
         let mut csv = CSVFile::default();
         let res = File::open(path);
@@ -68,6 +66,59 @@ impl CSVFile {
     }
 }
 
+/// Inserts the records of a CSV file used before the database, in a single transaction.
+/// `import` tells how many rows a record added, `None` when the record is corrupted.
+/// Gives the rows added and the corrupted records, blank lines are ignored.
+fn import_legacy_records<'a>(
+    database: &Database,
+    records: &'a [CSVRecord],
+    mut import: impl FnMut(&Transaction, &CSVRecord) -> rusqlite::Result<Option<usize>>,
+) -> rusqlite::Result<(usize, Vec<&'a CSVRecord>)> {
+    let mut connection = database.connection();
+    let transaction = connection.transaction()?;
+    let mut imported = 0;
+    let mut skipped = vec![];
+    for record in records.iter().filter(|record| record.iter().any(|field| !field.is_empty())) {
+        match import(&transaction, record)? {
+            Some(rows) => imported += rows,
+            None => skipped.push(record),
+        }
+    }
+    transaction.commit()?;
+    Ok((imported, skipped))
+}
+
+/// Imports the CSV file at `path` used before the database, then renames it so it's imported once.
+/// Rows already in the database are left untouched. Corrupted records are logged and kept in a
+/// `.skipped` copy, which is imported on the next start once fixed and renamed back to `path`.
+fn import_legacy_csv(
+    database: &Database,
+    path: &str,
+    import: impl FnMut(&Transaction, &CSVRecord) -> rusqlite::Result<Option<usize>>,
+) -> rusqlite::Result<()> {
+    let file = match CSVFile::parse(path) {
+        Ok(file) => file,
+        _ => return Ok(()),
+    };
+    let (imported, skipped) = import_legacy_records(database, &file.records, import)?;
+    info!(imported, skipped = skipped.len(), total = file.records.len(), path, "Imported legacy file");
+    if !skipped.is_empty() {
+        let copy = format!("{}.skipped", path);
+        let lines: String = std::iter::once(&file.headers).chain(skipped.iter().copied())
+            .map(|record| format!("{}\n", record.join(";")))
+            .collect();
+        skipped.iter().for_each(|record| warn!(path, record = %record.join(";"), "Skipping corrupted record"));
+        if let Err(why) = fs::write(&copy, lines) {
+            error!(path, %copy, error = ?why, "Error keeping the skipped records");
+        }
+    }
+    let backup = format!("{}.imported", path);
+    if let Err(why) = fs::rename(path, &backup) {
+        error!(path, %backup, error = ?why, "Error renaming legacy file");
+    }
+    Ok(())
+}
+
 #[derive(Debug)]
 pub struct SkillName {
     pub name: String,
@@ -93,6 +144,16 @@ impl SkillNameStore {
     pub fn get_from_id(&self, id: u32) -> Option<&SkillName> {
         self.0.get(&id)
     }
+
+    pub fn find_by_name(&self, name: &str) -> Option<u32> {
+        self.0.iter()
+            .find(|(_, skill)| skill.name.eq_ignore_ascii_case(name.trim()))
+            .map(|(id, _)| *id)
+    }
+
+    pub fn names(&self) -> impl Iterator<Item=&str> {
+        self.0.values().map(|skill| skill.name.as_str())
+    }
 }
 
 #[derive(Debug)]
@@ -101,6 +162,7 @@ pub struct SkillInfo {
     pub skill_icon: String,
     pub skill_infos: HashMap<String, u32>,
     pub skill_stats: HashMap<String, String>,
+    pub elite: bool,
 }
 
 #[derive(Debug)]
@@ -133,7 +195,8 @@ impl SkillInfoStore {
                         let v: Vec<&str> = stat.split('=').collect();
                         (v[0].to_string(), v[1].to_string())
                     }).collect();
-            store.0.insert(id, SkillInfo { skill_uri, skill_icon, skill_infos, skill_stats });
+            let elite = x.get(6).map(|elite| elite == "true").unwrap_or(false);
+            store.0.insert(id, SkillInfo { skill_uri, skill_icon, skill_infos, skill_stats, elite });
         }
         store
     }
@@ -167,6 +230,14 @@ impl SKillI18nStore {
                 Some((skill, info))
             })
     }
+
+    pub fn find_by_name(&self, lng: Language, name: &str) -> Option<u32> {
+        self.0.get(&lng).and_then(|store| store.find_by_name(name))
+    }
+
+    pub fn names(&self, lng: Language) -> Vec<&str> {
+        self.0.get(&lng).map(|store| store.names().collect()).unwrap_or_default()
+    }
 }
 
 #[derive(Debug)]
@@ -218,7 +289,14 @@ impl ProfessionStore {
 
 #[derive(Debug)]
 pub struct ZaishenQuestData {
-    pub name: String
+    pub name: String,
+    pub campaign: Option<String>,
+    pub region: Option<String>,
+    pub outpost: Option<String>,
+    /// Zaishen Coins given by the quest.
+    pub reward: Option<String>,
+    pub wiki_url: Option<String>,
+    pub map_url: Option<String>,
 }
 
 #[derive(Debug)]
@@ -230,7 +308,15 @@ impl ZaishenQuestStore {
         let mut store = Self { 0: vec![] };
         for x in csv.records {
             let name = x.get(1).unwrap().to_string();
-            store.0.push(ZaishenQuestData{name});
+            // the details are optional, rotations like the Vanguard quests only list names
+            let column = |i: usize| x.get(i).filter(|value| !value.is_empty()).cloned();
+            let campaign = column(2);
+            let region = column(3);
+            let outpost = column(4);
+            let reward = column(5);
+            let wiki_url = column(6);
+            let map_url = column(7);
+            store.0.push(ZaishenQuestData { name, campaign, region, outpost, reward, wiki_url, map_url });
         }
         store
     }
@@ -238,6 +324,60 @@ impl ZaishenQuestStore {
     pub fn get_from_id(&self, id: i64) -> Option<&ZaishenQuestData> {
         self.0.get(id as usize)
     }
+
+    pub fn find_by_name(&self, name: &str) -> Option<i64> {
+        self.0.iter()
+            .position(|quest| quest.name.eq_ignore_ascii_case(name.trim()))
+            .map(|id| id as i64)
+    }
+
+    pub fn names(&self) -> impl Iterator<Item=&str> {
+        self.0.iter().map(|quest| quest.name.as_str())
+    }
+}
+
+/// Profession and capturable elite skills of a Zaishen bounty target, indexed like `bz_*.csv`.
+#[derive(Debug, Default)]
+pub struct BountyBossData {
+    pub profession: Option<ProfessionType>,
+    pub elites: Vec<u32>,
+    /// Explorable area or dungeon the boss is found in.
+    pub location: Option<String>,
+}
+
+#[derive(Debug)]
+pub struct BountyBossStore(Vec<BountyBossData>);
+
+impl BountyBossStore {
+    pub fn from_csv(path: &str) -> Self {
+        let csv = CSVFile::parse(path).unwrap_or_else(|_| panic!("{} doesn't exist", path));
+        let mut store = Self(vec![]);
+        for x in csv.records {
+            // monsters without a profession are written 0
+            let profession = x.get(1)
+                .and_then(|profession| u32::from_str(profession).ok())
+                .filter(|profession| *profession > 0)
+                .map(ProfessionType::from);
+            let elites = x.get(2)
+                .map(|elites| elites.split('|').filter_map(|id| u32::from_str(id).ok()).collect())
+                .unwrap_or_default();
+            let location = x.get(3).filter(|location| !location.is_empty()).cloned();
+            store.0.push(BountyBossData { profession, elites, location });
+        }
+        store
+    }
+
+    pub fn get_from_id(&self, id: i64) -> Option<&BountyBossData> {
+        self.0.get(id as usize)
+    }
+
+    /// Bounty ids of the bosses an elite skill can be captured from.
+    pub fn bosses_with_elite(&self, skill: u32) -> Vec<i64> {
+        self.0.iter().enumerate()
+            .filter(|(_, boss)| boss.elites.contains(&skill))
+            .map(|(id, _)| id as i64)
+            .collect()
+    }
 }
 
 #[derive(Debug)]
@@ -263,6 +403,16 @@ impl BonusEventStore {
     pub fn get_from_id(&self, id: i64) -> Option<&BonusEventData> {
         self.0.get(id as usize)
     }
+
+    pub fn find_by_name(&self, name: &str) -> Option<i64> {
+        self.0.iter()
+            .position(|bonus| bonus.name.eq_ignore_ascii_case(name.trim()))
+            .map(|id| id as i64)
+    }
+
+    pub fn names(&self) -> impl Iterator<Item=&str> {
+        self.0.iter().map(|bonus| bonus.name.as_str())
+    }
 }
 
 
@@ -274,6 +424,29 @@ pub struct NicholasGiftData {
     pub campaign: String,
     pub item_url: String,
     pub location_url: String,
+    pub nearest_outposts: Vec<String>,
+}
+
+impl NicholasGiftData {
+    /// Items asked per present, read from the leading count of `item` like "3 Amber Chunks".
+    pub fn quantity(&self) -> u32 {
+        self.item.split_whitespace().next()
+            .and_then(|count| u32::from_str(count).ok())
+            .unwrap_or(1)
+    }
+
+    /// `item` without its leading count.
+    pub fn item_name(&self) -> &str {
+        match self.item.split_once(' ') {
+            Some((count, name)) if u32::from_str(count).is_ok() => name,
+            _ => &self.item,
+        }
+    }
+
+    /// Direct link to the map screenshot, `location_url` being its wiki file page.
+    pub fn map_image_url(&self) -> String {
+        self.location_url.replacen("/wiki/File:", "/wiki/Special:FilePath/", 1)
+    }
 }
 
 #[derive(Debug)]
@@ -290,7 +463,10 @@ impl NicholasGiftStore {
             let campaign = x.get(4).unwrap().to_string();
             let item_url = x.get(5).unwrap().to_string();
             let location_url = x.get(6).unwrap().to_string();
-            store.0.push(NicholasGiftData { item, location, region, campaign, item_url, location_url });
+            let nearest_outposts = x.get(7)
+                .map(|outposts| outposts.split('|').filter(|outpost| !outpost.is_empty()).map(String::from).collect())
+                .unwrap_or_default();
+            store.0.push(NicholasGiftData { item, location, region, campaign, item_url, location_url, nearest_outposts });
         }
         store
     }
@@ -298,6 +474,45 @@ impl NicholasGiftStore {
     pub fn get_from_id(&self, id: i64) -> Option<&NicholasGiftData> {
         self.0.get(id as usize)
     }
+
+    /// Accepts the item with or without its count, e.g. "3 Amber Chunks" or "Amber Chunks".
+    pub fn find_by_name(&self, name: &str) -> Option<i64> {
+        let name = name.trim();
+        self.0.iter()
+            .position(|gift| gift.item.eq_ignore_ascii_case(name) || gift.item_name().eq_ignore_ascii_case(name))
+            .map(|id| id as i64)
+    }
+
+    pub fn names(&self) -> impl Iterator<Item=&str> {
+        self.0.iter().map(|gift| gift.item.as_str())
+    }
+}
+
+/// Daily request of Nicholas Sandford in Pre-Searing Ascalon.
+#[derive(Debug)]
+pub struct PreSearingGiftData {
+    pub item: String,
+    pub item_url: String,
+}
+
+#[derive(Debug)]
+pub struct PreSearingGiftStore(Vec<PreSearingGiftData>);
+
+impl PreSearingGiftStore {
+    pub fn from_csv(path: &str) -> Self {
+        let csv = CSVFile::parse(path).unwrap_or_else(|_| panic!("{} doesn't exist", path));
+        let mut store = Self(vec![]);
+        for x in csv.records {
+            let item = x.get(1).unwrap().to_string();
+            let item_url = x.get(2).unwrap().to_string();
+            store.0.push(PreSearingGiftData { item, item_url });
+        }
+        store
+    }
+
+    pub fn get_from_id(&self, id: i64) -> Option<&PreSearingGiftData> {
+        self.0.get(id as usize)
+    }
 }
 
 
@@ -327,7 +542,65 @@ impl SpecialEventStore {
     }
 }
 
+#[derive(Debug, Default)]
+pub struct EventBonusData {
+    pub pve: Vec<i64>,
+    pub pvp: Vec<i64>,
+}
+
+/// Weekly bonuses a special event turns on in addition to the regular rotation.
 #[derive(Debug)]
+pub struct EventBonusStore(HashMap<u32, EventBonusData>);
+
+fn parse_bonus_ids(raw: &str) -> Vec<i64> {
+    raw.split('|')
+        .filter(|id| !id.is_empty())
+        .map(|id| i64::from_str(id).unwrap())
+        .collect()
+}
+
+impl EventBonusStore {
+    pub fn from_csv(path: &str) -> Self {
+        let csv = CSVFile::parse(path).unwrap_or_else(|_| panic!("{} doesn't exist", path));
+        let mut hm = HashMap::new();
+        for x in csv.records {
+            let id = u32::from_str(x.first().unwrap()).unwrap();
+            let pve = x.get(1).map(|raw| parse_bonus_ids(raw)).unwrap_or_default();
+            let pvp = x.get(2).map(|raw| parse_bonus_ids(raw)).unwrap_or_default();
+            hm.insert(id, EventBonusData { pve, pvp });
+        }
+        Self(hm)
+    }
+
+    pub fn get_from_id(&self, id: u32) -> Option<&EventBonusData> {
+        self.0.get(&id)
+    }
+
+    /// The weekly PvE bonus followed by those added by `events`, each paired with the event
+    /// that brings it.
+    pub fn effective_pve(&self, weekly: i64, events: &[SpecialEventPeriod]) -> Vec<(i64, Option<u32>)> {
+        self.effective(weekly, events, |data| &data.pve)
+    }
+
+    pub fn effective_pvp(&self, weekly: i64, events: &[SpecialEventPeriod]) -> Vec<(i64, Option<u32>)> {
+        self.effective(weekly, events, |data| &data.pvp)
+    }
+
+    fn effective(&self, weekly: i64, events: &[SpecialEventPeriod], select: fn(&EventBonusData) -> &Vec<i64>) -> Vec<(i64, Option<u32>)> {
+        let mut bonuses = vec![(weekly, None)];
+        for event in events {
+            let extra = self.get_from_id(event.0).map(select).into_iter().flatten();
+            for id in extra {
+                if !bonuses.iter().any(|(bonus, _)| bonus == id) {
+                    bonuses.push((*id, Some(event.0)));
+                }
+            }
+        }
+        bonuses
+    }
+}
+
+#[derive(Debug, Clone)]
 pub struct SpecialEventPeriod(pub u32, pub DateTimeRange<Utc>);
 
 impl SpecialEventPeriod {
@@ -344,23 +617,6 @@ impl SpecialEventPeriod {
     }
 }
 
-pub fn get_special_events_time_range() -> Vec<SpecialEventPeriod> {
-    let mut events = vec![];
-    let path = "datas/special_events.csv";
-    let csv = CSVFile::parse(path).expect(&format!("{} doesn't exist", path));
-    for x in csv.records {
-        let id = x.get(0).unwrap();
-        let id = u32::from_str(id).unwrap();
-        let start = x.get(1).unwrap();
-        let start = Utc.datetime_from_str(start, &"%+").unwrap();
-        let end = x.get(2).unwrap();
-        let end = Utc.datetime_from_str(end, &"%+").unwrap();
-        events.push(SpecialEventPeriod(id, DateTimeRange::new(start, end)));
-    }
-    events
-}
-
-
 #[derive(Debug)]
 pub struct I18nMessageStore(HashMap<String, String>);
 
@@ -378,6 +634,46 @@ impl I18nMessageStore {
         I18nMessageStore(hm)
     }
 
+    /// `field` of the `help-<command>-<field>` key, missing when the command has no such field.
+    pub fn help(&self, command: &str, field: &str) -> Option<Msg> {
+        self.0.get(&format!("help-{}-{}", command, field)).map(String::as_str)
+    }
+    pub fn help_group(&self, group: &str) -> Option<Msg> {
+        self.0.get(&format!("help-group-{}", group)).map(String::as_str)
+    }
+    pub fn permission_denied(&self) -> Msg {
+        self.0.get("permission-denied").expect("'permission-denied' key is missing")
+    }
+    pub fn permission_manage_guild(&self) -> Msg {
+        self.0.get("permission-manage-guild").expect("'permission-manage-guild' key is missing")
+    }
+    pub fn audit_change(&self) -> Msg {
+        self.0.get("audit-change").expect("'audit-change' key is missing")
+    }
+    pub fn rules_disabled(&self) -> Msg {
+        self.0.get("rules-disabled").expect("'rules-disabled' key is missing")
+    }
+    pub fn rules_channels(&self) -> Msg {
+        self.0.get("rules-channels").expect("'rules-channels' key is missing")
+    }
+    pub fn rate_limited(&self) -> Msg {
+        self.0.get("rate-limited").expect("'rate-limited' key is missing")
+    }
+    pub fn help_headline(&self) -> Msg {
+        self.0.get("help-headline").expect("'help-headline' key is missing")
+    }
+    pub fn help_details(&self) -> Msg {
+        self.0.get("help-details").expect("'help-details' key is missing")
+    }
+    pub fn help_usage(&self) -> Msg {
+        self.0.get("help-usage").expect("'help-usage' key is missing")
+    }
+    pub fn help_examples(&self) -> Msg {
+        self.0.get("help-examples").expect("'help-examples' key is missing")
+    }
+    pub fn help_unknown(&self) -> Msg {
+        self.0.get("help-unknown").expect("'help-unknown' key is missing")
+    }
     pub fn time_days(&self) -> Msg {
         self.0.get("time-days").expect("'time-days' key is missing")
     }
@@ -414,9 +710,75 @@ impl I18nMessageStore {
     pub fn zaishen_quest_reset(&self) -> Msg {
         self.0.get("zaishen-quest-reset").expect("'zaishen-quest-reset' key is missing")
     }
+    pub fn zaishen_quest_outpost(&self) -> Msg {
+        self.0.get("zaishen-quest-outpost").expect("'zaishen-quest-outpost' key is missing")
+    }
+    pub fn zaishen_quest_reward(&self) -> Msg {
+        self.0.get("zaishen-quest-reward").expect("'zaishen-quest-reward' key is missing")
+    }
+    pub fn zaishen_quest_coins(&self) -> Msg {
+        self.0.get("zaishen-quest-coins").expect("'zaishen-quest-coins' key is missing")
+    }
+    pub fn zaishen_quest_profession(&self) -> Msg {
+        self.0.get("zaishen-quest-profession").expect("'zaishen-quest-profession' key is missing")
+    }
+    pub fn zaishen_quest_location(&self) -> Msg {
+        self.0.get("zaishen-quest-location").expect("'zaishen-quest-location' key is missing")
+    }
+    pub fn zaishen_quest_capture(&self) -> Msg {
+        self.0.get("zaishen-quest-capture").expect("'zaishen-quest-capture' key is missing")
+    }
+    pub fn capture_headline(&self) -> Msg {
+        self.0.get("capture-headline").expect("'capture-headline' key is missing")
+    }
+    pub fn capture_today(&self) -> Msg {
+        self.0.get("capture-today").expect("'capture-today' key is missing")
+    }
+    pub fn capture_next(&self) -> Msg {
+        self.0.get("capture-next").expect("'capture-next' key is missing")
+    }
+    pub fn capture_unknown(&self) -> Msg {
+        self.0.get("capture-unknown").expect("'capture-unknown' key is missing")
+    }
+    pub fn capture_not_elite(&self) -> Msg {
+        self.0.get("capture-not-elite").expect("'capture-not-elite' key is missing")
+    }
+    pub fn capture_no_boss(&self) -> Msg {
+        self.0.get("capture-no-boss").expect("'capture-no-boss' key is missing")
+    }
+    pub fn rotation_next(&self) -> Msg {
+        self.0.get("rotation-next").expect("'rotation-next' key is missing")
+    }
+    pub fn rotation_active(&self) -> Msg {
+        self.0.get("rotation-active").expect("'rotation-active' key is missing")
+    }
+    pub fn rotation_unknown(&self) -> Msg {
+        self.0.get("rotation-unknown").expect("'rotation-unknown' key is missing")
+    }
     pub fn zaishen_quest_tomorrow_headline(&self) -> Msg {
         self.0.get("zaishen-quest-tomorrow-headline").expect("'zaishen-quest-tomorrow-headline' key is missing")
     }
+    pub fn vanguard_headline(&self) -> Msg {
+        self.0.get("vanguard-headline").expect("'vanguard-headline' key is missing")
+    }
+    pub fn vanguard_quest(&self) -> Msg {
+        self.0.get("vanguard-quest").expect("'vanguard-quest' key is missing")
+    }
+    pub fn wanted_headline(&self) -> Msg {
+        self.0.get("wanted-headline").expect("'wanted-headline' key is missing")
+    }
+    pub fn wanted_bounty(&self) -> Msg {
+        self.0.get("wanted-bounty").expect("'wanted-bounty' key is missing")
+    }
+    pub fn daily_today(&self) -> Msg {
+        self.0.get("daily-today").expect("'daily-today' key is missing")
+    }
+    pub fn daily_tomorrow(&self) -> Msg {
+        self.0.get("daily-tomorrow").expect("'daily-tomorrow' key is missing")
+    }
+    pub fn daily_reset(&self) -> Msg {
+        self.0.get("daily-reset").expect("'daily-reset' key is missing")
+    }
     pub fn nicholas_gift_headline(&self) -> Msg {
         self.0.get("nicholas-gift-headline").expect("'nicholas-gift-headline' key is missing")
     }
@@ -438,6 +800,78 @@ impl I18nMessageStore {
     pub fn nicholas_gift_found(&self) -> Msg {
         self.0.get("nicholas-gift-found").expect("'nicholas-gift-found' key is missing")
     }
+    pub fn nicholas_gift_needed(&self) -> Msg {
+        self.0.get("nicholas-gift-needed").expect("'nicholas-gift-needed' key is missing")
+    }
+    pub fn nicholas_gift_outposts(&self) -> Msg {
+        self.0.get("nicholas-gift-outposts").expect("'nicholas-gift-outposts' key is missing")
+    }
+    pub fn nicholas_gift_count(&self) -> Msg {
+        self.0.get("nicholas-gift-count").expect("'nicholas-gift-count' key is missing")
+    }
+    pub fn nicholas_done_marked(&self) -> Msg {
+        self.0.get("nicholas-done-marked").expect("'nicholas-done-marked' key is missing")
+    }
+    pub fn nicholas_done_already(&self) -> Msg {
+        self.0.get("nicholas-done-already").expect("'nicholas-done-already' key is missing")
+    }
+    pub fn nicholas_streak_current(&self) -> Msg {
+        self.0.get("nicholas-streak-current").expect("'nicholas-streak-current' key is missing")
+    }
+    pub fn nicholas_streak_best(&self) -> Msg {
+        self.0.get("nicholas-streak-best").expect("'nicholas-streak-best' key is missing")
+    }
+    pub fn nicholas_weeks(&self) -> Msg {
+        self.0.get("nicholas-weeks").expect("'nicholas-weeks' key is missing")
+    }
+    pub fn nicholas_cycle_collected(&self) -> Msg {
+        self.0.get("nicholas-cycle-collected").expect("'nicholas-cycle-collected' key is missing")
+    }
+    pub fn nicholas_history_headline(&self) -> Msg {
+        self.0.get("nicholas-history-headline").expect("'nicholas-history-headline' key is missing")
+    }
+    pub fn nicholas_history_done(&self) -> Msg {
+        self.0.get("nicholas-history-done").expect("'nicholas-history-done' key is missing")
+    }
+    pub fn nicholas_history_missed(&self) -> Msg {
+        self.0.get("nicholas-history-missed").expect("'nicholas-history-missed' key is missing")
+    }
+    pub fn nicholas_reminder_on(&self) -> Msg {
+        self.0.get("nicholas-reminder-on").expect("'nicholas-reminder-on' key is missing")
+    }
+    pub fn nicholas_history_weeks(&self) -> Msg {
+        self.0.get("nicholas-history-weeks").expect("'nicholas-history-weeks' key is missing")
+    }
+    pub fn nicholas_reminder_usage(&self) -> Msg {
+        self.0.get("nicholas-reminder-usage").expect("'nicholas-reminder-usage' key is missing")
+    }
+    pub fn nicholas_reminder_off(&self) -> Msg {
+        self.0.get("nicholas-reminder-off").expect("'nicholas-reminder-off' key is missing")
+    }
+    pub fn nicholas_reminder(&self) -> Msg {
+        self.0.get("nicholas-reminder").expect("'nicholas-reminder' key is missing")
+    }
+    pub fn pre_searing_headline(&self) -> Msg {
+        self.0.get("pre-searing-headline").expect("'pre-searing-headline' key is missing")
+    }
+    pub fn pre_searing_today(&self) -> Msg {
+        self.0.get("pre-searing-today").expect("'pre-searing-today' key is missing")
+    }
+    pub fn pre_searing_tomorrow(&self) -> Msg {
+        self.0.get("pre-searing-tomorrow").expect("'pre-searing-tomorrow' key is missing")
+    }
+    pub fn pre_searing_on(&self) -> Msg {
+        self.0.get("pre-searing-on").expect("'pre-searing-on' key is missing")
+    }
+    pub fn pre_searing_per(&self) -> Msg {
+        self.0.get("pre-searing-per").expect("'pre-searing-per' key is missing")
+    }
+    pub fn pre_searing_date(&self) -> Msg {
+        self.0.get("pre-searing-date").expect("'pre-searing-date' key is missing")
+    }
+    pub fn pre_searing_reset(&self) -> Msg {
+        self.0.get("pre-searing-reset").expect("'pre-searing-reset' key is missing")
+    }
     pub fn bonus_headline(&self) -> Msg {
         self.0.get("bonus-headline").expect("'bonus-headline' key is missing")
     }
@@ -453,86 +887,619 @@ impl I18nMessageStore {
     pub fn bonus_expire(&self) -> Msg {
         self.0.get("bonus-expire").expect("'bonus-next-expire' key is missing")
     }
+    pub fn bonus_event(&self) -> Msg {
+        self.0.get("bonus-event").expect("'bonus-event' key is missing")
+    }
+    pub fn event_bonus_headline(&self) -> Msg {
+        self.0.get("event-bonus-headline").expect("'event-bonus-headline' key is missing")
+    }
     pub fn bonus_next_start(&self) -> Msg {
         self.0.get("bonus-next-start").expect("'bonus-next-start' key is missing")
     }
+    pub fn today_headline(&self) -> Msg {
+        self.0.get("today-headline").expect("'today-headline' key is missing")
+    }
+    pub fn today_zaishen(&self) -> Msg {
+        self.0.get("today-zaishen").expect("'today-zaishen' key is missing")
+    }
+    pub fn today_nicholas(&self) -> Msg {
+        self.0.get("today-nicholas").expect("'today-nicholas' key is missing")
+    }
+    pub fn today_pre_searing(&self) -> Msg {
+        self.0.get("today-pre-searing").expect("'today-pre-searing' key is missing")
+    }
+    pub fn today_bonuses(&self) -> Msg {
+        self.0.get("today-bonuses").expect("'today-bonuses' key is missing")
+    }
+    pub fn today_events(&self) -> Msg {
+        self.0.get("today-events").expect("'today-events' key is missing")
+    }
+    pub fn today_event_until(&self) -> Msg {
+        self.0.get("today-event-until").expect("'today-event-until' key is missing")
+    }
+    pub fn today_resets(&self) -> Msg {
+        self.0.get("today-resets").expect("'today-resets' key is missing")
+    }
+    pub fn today_daily_reset(&self) -> Msg {
+        self.0.get("today-daily-reset").expect("'today-daily-reset' key is missing")
+    }
+    pub fn today_weekly_reset(&self) -> Msg {
+        self.0.get("today-weekly-reset").expect("'today-weekly-reset' key is missing")
+    }
+    pub fn forecast_zaishen_headline(&self) -> Msg {
+        self.0.get("forecast-zaishen-headline").expect("'forecast-zaishen-headline' key is missing")
+    }
+    pub fn forecast_nicholas_headline(&self) -> Msg {
+        self.0.get("forecast-nicholas-headline").expect("'forecast-nicholas-headline' key is missing")
+    }
+    pub fn forecast_bonus_headline(&self) -> Msg {
+        self.0.get("forecast-bonus-headline").expect("'forecast-bonus-headline' key is missing")
+    }
+    pub fn forecast_pre_searing(&self) -> Msg {
+        self.0.get("forecast-pre-searing").expect("'forecast-pre-searing' key is missing")
+    }
+    pub fn forecast_days(&self) -> Msg {
+        self.0.get("forecast-days").expect("'forecast-days' key is missing")
+    }
+    pub fn forecast_weeks(&self) -> Msg {
+        self.0.get("forecast-weeks").expect("'forecast-weeks' key is missing")
+    }
+    pub fn forecast_page(&self) -> Msg {
+        self.0.get("forecast-page").expect("'forecast-page' key is missing")
+    }
+    pub fn ics_attached(&self) -> Msg {
+        self.0.get("ics-attached").expect("'ics-attached' key is missing")
+    }
+    pub fn ics_feed(&self) -> Msg {
+        self.0.get("ics-feed").expect("'ics-feed' key is missing")
+    }
+    pub fn ics_dates(&self) -> Msg {
+        self.0.get("ics-dates").expect("'ics-dates' key is missing")
+    }
+    pub fn ics_range(&self) -> Msg {
+        self.0.get("ics-range").expect("'ics-range' key is missing")
+    }
+    pub fn countdown_custom(&self) -> Msg {
+        self.0.get("countdown-custom").expect("'countdown-custom' key is missing")
+    }
+    pub fn countdown_done(&self) -> Msg {
+        self.0.get("countdown-done").expect("'countdown-done' key is missing")
+    }
+    pub fn countdown_target(&self) -> Msg {
+        self.0.get("countdown-target").expect("'countdown-target' key is missing")
+    }
+    pub fn countdown_skipped(&self) -> Msg {
+        self.0.get("countdown-skipped").expect("'countdown-skipped' key is missing")
+    }
+    pub fn countdown_range(&self) -> Msg {
+        self.0.get("countdown-range").expect("'countdown-range' key is missing")
+    }
     pub fn skill_prefix(&self) -> Msg {
         self.0.get("skill-prefix").expect("'skill-prefix' key is missing")
     }
+
+    pub fn skill_invalid_code(&self) -> Msg {
+        self.0.get("skill-invalid-code").expect("'skill-invalid-code' key is missing")
+    }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct GuildConfigData {
     language: Language,
-    utc: i32,
+    timezone: Tz,
+    timestamps: TimestampStyle,
+    prefix: String,
+    /// Channel the changes of the settings are reported to.
+    audit_channel: Option<u64>,
+    /// Roles allowed to change the settings on top of the members with Manage Server.
+    config_roles: BTreeSet<u64>,
 }
 
-impl GuildConfigData {}
+impl Default for GuildConfigData {
+    fn default() -> Self {
+        Self {
+            language: Language::English,
+            timezone: Tz::UTC,
+            timestamps: TimestampStyle::Discord,
+            prefix: DEFAULT_PREFIX.to_string(),
+            audit_channel: None,
+            config_roles: BTreeSet::new(),
+        }
+    }
+}
+
+impl GuildConfigData {
+    /// Line of the CSV file used before the database, `None` when it's corrupted.
+    fn from_record(record: &CSVRecord) -> Option<(GuildRawId, Self)> {
+        let guild = u64::from_str(record.first()?).ok()?;
+        let language = record.get(1)?.parse::<Language>().ok()?;
+        // older files stored a plain UTC offset, which parse_timezone still accepts
+        let timezone = record.get(2).and_then(|timezone| parse_timezone(timezone).ok()).unwrap_or(Tz::UTC);
+        let timestamps = record.get(3)
+            .and_then(|style| style.parse::<TimestampStyle>().ok())
+            .unwrap_or(TimestampStyle::Discord);
+        let prefix = record.get(4)
+            .filter(|prefix| !prefix.is_empty())
+            .cloned()
+            .unwrap_or_else(|| DEFAULT_PREFIX.to_string());
+        Some((guild, Self { language, timezone, timestamps, prefix, ..Self::default() }))
+    }
+
+    fn from_row(row: &Row) -> rusqlite::Result<(GuildRawId, Self)> {
+        let guild = row.get(0)?;
+        let language: String = row.get(1)?;
+        let timezone: String = row.get(2)?;
+        let timestamps: String = row.get(3)?;
+        let config = Self {
+            language: language.parse().unwrap_or(Language::English),
+            timezone: parse_timezone(&timezone).unwrap_or(Tz::UTC),
+            timestamps: timestamps.parse().unwrap_or(TimestampStyle::Discord),
+            prefix: row.get(4)?,
+            audit_channel: row.get(5)?,
+            config_roles: BTreeSet::new(),
+        };
+        Ok((guild, config))
+    }
+
+    fn params(&self, guild: GuildRawId) -> [Box<dyn ToSql>; 6] {
+        [
+            Box::new(guild),
+            Box::new(self.language.to_string()),
+            Box::new(self.timezone.name()),
+            Box::new(self.timestamps.to_string()),
+            Box::new(self.prefix.clone()),
+            Box::new(self.audit_channel),
+        ]
+    }
+
+    fn import(transaction: &Transaction, record: &CSVRecord) -> rusqlite::Result<Option<usize>> {
+        Self::from_record(record)
+            .map(|(guild, config)| transaction.execute(
+                "INSERT OR IGNORE INTO guild_config (guild, language, timezone, timestamps, prefix, audit_channel) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params_from_iter(config.params(guild)),
+            ))
+            .transpose()
+    }
+}
 
 type GuildRawId = u64;
 
+const LEGACY_GUILDS_CONFIG_PATH: &str = "user-config.csv";
+
+/// Settings of the guilds, kept in memory and written through to the database.
 #[derive(Debug)]
-pub struct GuildsConfig(HashMap<GuildRawId, GuildConfigData>);
+pub struct GuildsConfig {
+    database: Database,
+    guilds: HashMap<GuildRawId, GuildConfigData>,
+}
 
 impl GuildsConfig {
-    pub fn load() -> Self {
-        let mut hm = HashMap::new();
-        match CSVFile::parse("user-config.csv") {
-            Ok(file) => {
-                for x in file.records {
-                    let guild = u64::from_str(&x[0]).unwrap();
-                    let language = Language::from(&x[1]).unwrap();
-                    let utc = i32::from_str(&x[2]).unwrap();
-                    hm.insert(guild, GuildConfigData { language, utc });
+    pub fn load(database: Database) -> rusqlite::Result<Self> {
+        let mut guilds: HashMap<_, _> = database.connection()
+            .prepare("SELECT guild, language, timezone, timestamps, prefix, audit_channel FROM guild_config")?
+            .query_map([], GuildConfigData::from_row)?
+            .collect::<rusqlite::Result<_>>()?;
+        let roles = database.connection()
+            .prepare("SELECT guild, role FROM guild_config_role")?
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<rusqlite::Result<Vec<(GuildRawId, u64)>>>()?;
+        for (guild, role) in roles {
+            guilds.entry(guild).or_default().config_roles.insert(role);
+        }
+        Ok(Self { database, guilds })
+    }
+
+    /// Copies the guilds of the CSV file used before the database, see [`import_legacy_csv`].
+    pub fn import_csv(database: &Database) -> rusqlite::Result<()> {
+        import_legacy_csv(database, LEGACY_GUILDS_CONFIG_PATH, GuildConfigData::import)
+    }
+
+    /// The change is only kept in memory once the database accepted it.
+    fn update(&mut self, guild: GuildRawId, change: impl FnOnce(&mut GuildConfigData)) -> rusqlite::Result<()> {
+        let mut config = self.guilds.get(&guild).cloned().unwrap_or_default();
+        change(&mut config);
+        self.database.connection().execute(
+            "INSERT INTO guild_config (guild, language, timezone, timestamps, prefix, audit_channel) VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+             ON CONFLICT (guild) DO UPDATE SET language = excluded.language, timezone = excluded.timezone,
+                timestamps = excluded.timestamps, prefix = excluded.prefix, audit_channel = excluded.audit_channel",
+            params_from_iter(config.params(guild)),
+        )?;
+        self.guilds.insert(guild, config);
+        Ok(())
+    }
+
+    pub fn set_language(&mut self, guild: GuildRawId, lng: Language) -> rusqlite::Result<()> {
+        self.update(guild, |config| config.language = lng)
+    }
+    pub fn set_timezone(&mut self, guild: GuildRawId, timezone: Tz) -> rusqlite::Result<()> {
+        self.update(guild, |config| config.timezone = timezone)
+    }
+    pub fn set_timestamp_style(&mut self, guild: GuildRawId, timestamps: TimestampStyle) -> rusqlite::Result<()> {
+        self.update(guild, |config| config.timestamps = timestamps)
+    }
+    pub fn set_prefix(&mut self, guild: GuildRawId, prefix: String) -> rusqlite::Result<()> {
+        self.update(guild, |config| config.prefix = prefix)
+    }
+    pub fn set_audit_channel(&mut self, guild: GuildRawId, channel: Option<u64>) -> rusqlite::Result<()> {
+        self.update(guild, |config| config.audit_channel = channel)
+    }
+    /// `false` when the role was already allowed.
+    pub fn add_config_role(&mut self, guild: GuildRawId, role: u64) -> rusqlite::Result<bool> {
+        let added = self.database.connection().execute(
+            "INSERT OR IGNORE INTO guild_config_role (guild, role) VALUES (?1, ?2)",
+            params![guild, role],
+        )? > 0;
+        self.guilds.entry(guild).or_default().config_roles.insert(role);
+        Ok(added)
+    }
+    /// `false` when the role wasn't allowed.
+    pub fn remove_config_role(&mut self, guild: GuildRawId, role: u64) -> rusqlite::Result<bool> {
+        let removed = self.database.connection().execute(
+            "DELETE FROM guild_config_role WHERE guild = ?1 AND role = ?2",
+            params![guild, role],
+        )? > 0;
+        if let Some(config) = self.guilds.get_mut(&guild) {
+            config.config_roles.remove(&role);
+        }
+        Ok(removed)
+    }
+    pub fn get_config_roles(&self, guild: GuildRawId) -> Vec<u64> {
+        self.guilds.get(&guild)
+            .map(|config| config.config_roles.iter().copied().collect())
+            .unwrap_or_default()
+    }
+    pub fn get_audit_channel(&self, guild: GuildRawId) -> Option<u64> {
+        self.guilds.get(&guild).and_then(|config| config.audit_channel)
+    }
+    pub fn get_prefix(&self, guild: GuildRawId) -> &str {
+        self.guilds.get(&guild)
+            .map(|config| config.prefix.as_str())
+            .unwrap_or(DEFAULT_PREFIX)
+    }
+    pub fn get_timestamp_style(&self, guild: GuildRawId) -> TimestampStyle {
+        self.guilds.get(&guild)
+            .map(|config| config.timestamps)
+            .unwrap_or(TimestampStyle::Discord)
+    }
+    pub fn get_guild_config(&self, guild: GuildRawId) -> (Language, Tz) {
+        self.guilds.get(&guild)
+            .map(|config| (config.language, config.timezone))
+            .unwrap_or((Language::English, Tz::UTC))
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct UserConfigData {
+    timezone: Option<Tz>,
+    language: Option<Language>,
+}
+
+type UserRawId = u64;
+
+const LEGACY_USERS_CONFIG_PATH: &str = "users-config.csv";
+
+/// Settings of the users, kept in memory and written through to the database.
+#[derive(Debug)]
+pub struct UsersConfig {
+    database: Database,
+    users: HashMap<UserRawId, UserConfigData>,
+}
+
+impl UsersConfig {
+    pub fn load(database: Database) -> rusqlite::Result<Self> {
+        let users = database.connection()
+            .prepare("SELECT user, timezone, language FROM user_config")?
+            .query_map([], |row| {
+                let timezone: Option<String> = row.get(1)?;
+                let timezone = timezone.and_then(|timezone| parse_timezone(&timezone).ok());
+                let language: Option<String> = row.get(2)?;
+                let language = language.and_then(|language| language.parse().ok());
+                Ok((row.get(0)?, UserConfigData { timezone, language }))
+            })?
+            .collect::<rusqlite::Result<_>>()?;
+        Ok(Self { database, users })
+    }
+
+    /// Same as [`GuildsConfig::import_csv`] for the users' file.
+    pub fn import_csv(database: &Database) -> rusqlite::Result<()> {
+        import_legacy_csv(database, LEGACY_USERS_CONFIG_PATH, |transaction, record| {
+            let user = record.first().and_then(|user| u64::from_str(user).ok());
+            let timezone = record.get(1).and_then(|timezone| parse_timezone(timezone).ok());
+            user.zip(timezone)
+                .map(|(user, timezone)| transaction.execute(
+                    "INSERT OR IGNORE INTO user_config (user, timezone) VALUES (?1, ?2)",
+                    params![user, timezone.name()],
+                ))
+                .transpose()
+        })
+    }
+
+    /// `None` removes the user override so the guild time zone applies again.
+    pub fn set_timezone(&mut self, user: UserRawId, timezone: Option<Tz>) -> rusqlite::Result<()> {
+        self.database.connection().execute(
+            "INSERT INTO user_config (user, timezone) VALUES (?1, ?2)
+             ON CONFLICT (user) DO UPDATE SET timezone = excluded.timezone",
+            params![user, timezone.map(|tz| tz.name())],
+        )?;
+        self.users.entry(user).or_default().timezone = timezone;
+        Ok(())
+    }
+
+    /// `None` removes the user override so the channel or guild language applies again.
+    pub fn set_language(&mut self, user: UserRawId, language: Option<Language>) -> rusqlite::Result<()> {
+        self.database.connection().execute(
+            "INSERT INTO user_config (user, language) VALUES (?1, ?2)
+             ON CONFLICT (user) DO UPDATE SET language = excluded.language",
+            params![user, language.map(|language| language.to_string())],
+        )?;
+        self.users.entry(user).or_default().language = language;
+        Ok(())
+    }
+
+    pub fn get_timezone(&self, user: UserRawId) -> Option<Tz> {
+        self.users.get(&user).and_then(|config| config.timezone)
+    }
+
+    pub fn get_language(&self, user: UserRawId) -> Option<Language> {
+        self.users.get(&user).and_then(|config| config.language)
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct ChannelConfigData {
+    language: Option<Language>,
+}
+
+type ChannelRawId = u64;
+
+/// Overrides of the guild settings for a single channel, written through to the database.
+#[derive(Debug)]
+pub struct ChannelsConfig {
+    database: Database,
+    channels: HashMap<ChannelRawId, ChannelConfigData>,
+}
+
+impl ChannelsConfig {
+    pub fn load(database: Database) -> rusqlite::Result<Self> {
+        let channels = database.connection()
+            .prepare("SELECT channel, language FROM channel_config")?
+            .query_map([], |row| {
+                let language: Option<String> = row.get(1)?;
+                let language = language.and_then(|language| language.parse().ok());
+                Ok((row.get(0)?, ChannelConfigData { language }))
+            })?
+            .collect::<rusqlite::Result<_>>()?;
+        Ok(Self { database, channels })
+    }
+
+    /// `None` removes the channel override so the guild language applies again.
+    pub fn set_language(&mut self, channel: ChannelRawId, guild: GuildRawId, language: Option<Language>) -> rusqlite::Result<()> {
+        self.database.connection().execute(
+            "INSERT INTO channel_config (channel, guild, language) VALUES (?1, ?2, ?3)
+             ON CONFLICT (channel) DO UPDATE SET language = excluded.language",
+            params![channel, guild, language.map(|language| language.to_string())],
+        )?;
+        self.channels.entry(channel).or_default().language = language;
+        Ok(())
+    }
+
+    pub fn get_language(&self, channel: ChannelRawId) -> Option<Language> {
+        self.channels.get(&channel).and_then(|config| config.language)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use chrono_tz::Tz;
+
+    use std::collections::HashMap;
+
+    use chrono::{TimeZone, Utc};
+
+    use crate::enums::{Language, ProfessionType};
+    use crate::constants::{PRE_SEARING, PRE_SEARING_SIZE_CYCLE, SHINING_BLADE_BOUNTY, SHINING_BLADE_BOUNTY_SIZE_CYCLE, VANGUARD_QUEST, VANGUARD_QUEST_SIZE_CYCLE};
+    use crate::utils::{BountyBossStore, ChannelsConfig, EventBonusData, EventBonusStore, GuildConfigData, GuildsConfig, import_legacy_records, NicholasGiftData, NicholasGiftStore, PreSearingGiftStore, SpecialEventPeriod, UsersConfig, ZaishenQuestStore};
+    use crate::utils::database::Database;
+    use crate::utils::time::{DateTimeRange, TimestampStyle};
+
+    fn record(fields: &[&str]) -> Vec<String> {
+        fields.iter().map(|field| field.to_string()).collect()
+    }
+
+    fn event_week(id: u32) -> SpecialEventPeriod {
+        SpecialEventPeriod(id, DateTimeRange::new(Utc.with_ymd_and_hms(2022, 7, 28, 19, 0, 0).unwrap(), Utc.with_ymd_and_hms(2022, 8, 4, 19, 0, 0).unwrap()))
+    }
+
+    #[test]
+    pub fn daily_rotations_match_their_cycles() {
+        let at = Utc.with_ymd_and_hms(2020, 9, 7, 16, 0, 0).unwrap();
+        for lang in ["en_US", "fr_FR"] {
+            let vanguard = ZaishenQuestStore::from_csv(&format!("datas/vanguard_{}.csv", lang));
+            assert!(vanguard.get_from_id(VANGUARD_QUEST_SIZE_CYCLE - 1).is_some() && vanguard.get_from_id(VANGUARD_QUEST_SIZE_CYCLE).is_none());
+            let wanted = ZaishenQuestStore::from_csv(&format!("datas/wanted_{}.csv", lang));
+            assert!(wanted.get_from_id(SHINING_BLADE_BOUNTY_SIZE_CYCLE - 1).is_some() && wanted.get_from_id(SHINING_BLADE_BOUNTY_SIZE_CYCLE).is_none());
+            let pre_searing = PreSearingGiftStore::from_csv(&format!("datas/pre_searing_{}.csv", lang));
+            assert!(pre_searing.get_from_id(PRE_SEARING_SIZE_CYCLE - 1).is_some() && pre_searing.get_from_id(PRE_SEARING_SIZE_CYCLE).is_none());
+        }
+        let vanguard = ZaishenQuestStore::from_csv("datas/vanguard_en_US.csv");
+        assert_eq!(vanguard.get_from_id(VANGUARD_QUEST.index_at(at)).unwrap().name, "Vanguard Rescue: Farmer Hamnet");
+        let wanted = ZaishenQuestStore::from_csv("datas/wanted_en_US.csv");
+        assert_eq!(wanted.get_from_id(SHINING_BLADE_BOUNTY.index_at(at)).unwrap().name, "Wanted: Cerris");
+        let pre_searing = PreSearingGiftStore::from_csv("datas/pre_searing_en_US.csv");
+        assert_eq!(pre_searing.get_from_id(PRE_SEARING.index_at(at)).unwrap().item, "5 Baked Husks");
+    }
+
+    fn nicholas_gift(item: &str) -> NicholasGiftData {
+        NicholasGiftData {
+            item: item.to_string(),
+            location: "Ferndale".to_string(),
+            region: "Echovald Forest".to_string(),
+            campaign: "Factions".to_string(),
+            item_url: "https://wiki.guildwars.com/wiki/Amber_Chunk".to_string(),
+            location_url: "https://wiki.guildwars.com/wiki/File:Nicholas_the_Traveler_Ferndale_map.jpg".to_string(),
+            nearest_outposts: vec![],
+        }
+    }
+
+    #[test]
+    pub fn nicholas_gift_count_split_from_the_item() {
+        let amber = nicholas_gift("3 Amber Chunks");
+        assert_eq!((amber.quantity(), amber.item_name()), (3, "Amber Chunks"));
+        let kabob = nicholas_gift("1 Drake Kabob");
+        assert_eq!((kabob.quantity(), kabob.item_name()), (1, "Drake Kabob"));
+        // without a leading count a single item is asked, and numbers within the name are kept
+        let uncounted = nicholas_gift("Drake Kabob");
+        assert_eq!((uncounted.quantity(), uncounted.item_name()), (1, "Drake Kabob"));
+        let named = nicholas_gift("Mark 2 Golem");
+        assert_eq!((named.quantity(), named.item_name()), (1, "Mark 2 Golem"));
+        assert_eq!(amber.map_image_url(), "https://wiki.guildwars.com/wiki/Special:FilePath/Nicholas_the_Traveler_Ferndale_map.jpg");
+    }
+
+    #[test]
+    pub fn nicholas_gifts_list_their_outposts() {
+        for lang in ["en_US", "fr_FR"] {
+            let gifts = NicholasGiftStore::from_csv(&format!("datas/nicolas_traveler_{}.csv", lang));
+            let mut id = 0;
+            while let Some(gift) = gifts.get_from_id(id) {
+                assert!(!gift.nearest_outposts.is_empty(), "{} lacks outposts", gift.location);
+                id += 1;
+            }
+            assert_eq!(id, 137);
+        }
+        let gifts = NicholasGiftStore::from_csv("datas/nicolas_traveler_en_US.csv");
+        assert_eq!(gifts.get_from_id(53).unwrap().nearest_outposts, vec!["Ascalon City", "Sardelac Sanitarium"]);
+    }
+
+    #[test]
+    pub fn bounty_bosses_loaded() {
+        let bosses = BountyBossStore::from_csv("datas/bz_bosses.csv");
+        let bounties = ZaishenQuestStore::from_csv("datas/bz_en_US.csv");
+        let mut id = 0;
+        while let Some(bounty) = bounties.get_from_id(id) {
+            let boss = bosses.get_from_id(id).unwrap_or_else(|| panic!("{} isn't described", bounty.name));
+            assert!(boss.location.is_some(), "{} lacks a location", bounty.name);
+            id += 1;
+        }
+        assert!(bosses.get_from_id(id).is_none());
+        let chung = bosses.get_from_id(27).unwrap();
+        assert_eq!(bounties.get_from_id(27).unwrap().name, "Chung, the Attuned");
+        assert_eq!(chung.profession, Some(ProfessionType::Ritualist));
+        assert_eq!(chung.location.as_deref(), Some("Xaquang Skyway"));
+        assert_eq!(bosses.bosses_with_elite(1220), vec![27]);
+        // monsters have neither a profession nor elites
+        assert_eq!(bosses.get_from_id(5).map(|urgoz| (urgoz.profession, urgoz.elites.len())), Some((None, 0)));
+    }
+
+    #[test]
+    pub fn zaishen_quests_located() {
+        for lang in ["en_US", "fr_FR"] {
+            for kind in ["mz", "bz", "vz"] {
+                let quests = ZaishenQuestStore::from_csv(&format!("datas/{}_{}.csv", kind, lang));
+                let mut id = 0;
+                while let Some(quest) = quests.get_from_id(id) {
+                    assert!(quest.campaign.is_some() && quest.region.is_some() && quest.wiki_url.is_some(), "{} {} lacks details", kind, quest.name);
+                    assert!(kind != "vz" || quest.outpost.is_some(), "{} {} lacks an outpost", kind, quest.name);
+                    id += 1;
                 }
-                Self(hm)
+                assert!(id > 0);
             }
-            _ => Self(Default::default())
         }
+        let vanquish = ZaishenQuestStore::from_csv("datas/vz_fr_FR.csv");
+        let jaya_bluffs = vanquish.get_from_id(0).unwrap();
+        assert_eq!((jaya_bluffs.campaign.as_deref(), jaya_bluffs.region.as_deref()), (Some("Factions"), Some("Iles de Shing Jea")));
+        assert_eq!(jaya_bluffs.outpost.as_deref(), Some("Port de Seitung"));
+        assert_eq!(jaya_bluffs.map_url.as_deref(), Some("https://wiki.guildwars.com/wiki/Special:FilePath/Nicholas_the_Traveler_Jaya_Bluffs_map.jpg"));
     }
 
-    fn save(&self) {
-        let func: fn(Vec<&str>) -> Vec<String> = |vec| vec.iter().map(|s| (*s).to_string()).collect();
-        let headers = func(vec!["guild", "language", "utc"]);
-        let records: Vec<CSVRecord> = self.0.iter()
-            .map(|item| {
-                let guild = format!("{}", item.0);
-                let utc = format!("{}", item.1.utc);
-                let lang = format!("{:?}", item.1.language);
-                vec![guild, lang, utc]
-            }).collect();
-        CSVFile::save("user-config.csv", headers, records);
-    }
-
-    pub fn set_language(&mut self, guild: GuildRawId, lng: Language) {
-        self.0.get_mut(&guild)
-            .and_then(|config| {
-                config.language = lng;
-                Some(true)
-            })
-            .or_else(|| {
-                let config = GuildConfigData { language: lng, utc: 0 };
-                self.0.insert(guild, config);
-                Some(true)
-            });
-        self.save();
-    }
-    pub fn set_utc(&mut self, guild: GuildRawId, utc: i32) {
-        self.0.get_mut(&guild)
-            .and_then(|config| {
-                config.utc = utc;
-                Some(true)
-            })
-            .or_else(|| {
-                let config = GuildConfigData { language: Language::English, utc };
-                self.0.insert(guild, config);
-                Some(true)
-            });
-        self.save();
-    }
-    pub fn get_guild_config(&self, guild: GuildRawId) -> (Language, i32) {
-        self.0.get(&guild)
-            .and_then(|config| Some((config.language, config.utc)))
-            .unwrap_or((Language::English, 0))
-    }
-}
\ No newline at end of file
+    #[test]
+    pub fn event_bonuses_added_to_the_weekly_one() {
+        let bonuses = EventBonusStore::from_csv("datas/special_events_bonus.csv");
+        // Wayfarer's Reverie during the Northern Support week doesn't list Northern Support twice
+        assert_eq!(bonuses.effective_pve(4, &[event_week(7)]), vec![(4, None), (0, Some(7)), (1, Some(7)), (7, Some(7))]);
+        assert_eq!(bonuses.effective_pvp(2, &[event_week(7)]), vec![(2, None)]);
+        assert_eq!(bonuses.effective_pve(4, &[]), vec![(4, None)]);
+    }
+
+    #[test]
+    pub fn event_bonuses_paired_with_the_first_event_bringing_them() {
+        let mut events = HashMap::new();
+        events.insert(1, EventBonusData { pve: vec![2, 3], pvp: vec![1] });
+        events.insert(2, EventBonusData { pve: vec![3, 5], pvp: vec![] });
+        let bonuses = EventBonusStore(events);
+        assert_eq!(bonuses.effective_pve(5, &[event_week(1), event_week(2)]), vec![(5, None), (2, Some(1)), (3, Some(1))]);
+        assert_eq!(bonuses.effective_pvp(0, &[event_week(2), event_week(1)]), vec![(0, None), (1, Some(1))]);
+        assert_eq!(bonuses.effective_pve(0, &[event_week(3)]), vec![(0, None)]);
+    }
+
+    #[test]
+    pub fn guilds_config_persisted() {
+        let database = Database::open_in_memory().unwrap();
+        let records = vec![
+            record(&["1", "French", "Europe/Paris", "plain", "!"]),
+            record(&["2", "English", "+2"]),
+            record(&["not a guild", "French"]),
+            record(&["3"]),
+            record(&[""]),
+        ];
+        let (imported, skipped) = import_legacy_records(&database, &records, GuildConfigData::import).unwrap();
+        assert_eq!((imported, skipped), (2, vec![&records[2], &records[3]]));
+        let mut config = GuildsConfig::load(database.clone()).unwrap();
+        assert_eq!(config.get_guild_config(1), (Language::French, Tz::Europe__Paris));
+        assert_eq!(config.get_timestamp_style(1), TimestampStyle::Plain);
+        assert_eq!(config.get_prefix(2), "-");
+        config.set_prefix(1, "?".to_string()).unwrap();
+        config.set_language(4, Language::French).unwrap();
+        // a second import never overwrites what changed since
+        assert_eq!(import_legacy_records(&database, &records, GuildConfigData::import).unwrap().0, 0);
+        let config = GuildsConfig::load(database).unwrap();
+        assert_eq!(config.get_prefix(1), "?");
+        assert_eq!(config.get_guild_config(4), (Language::French, Tz::UTC));
+        assert_eq!(config.get_guild_config(3), (Language::English, Tz::UTC));
+    }
+
+    #[test]
+    pub fn config_roles_and_audit_channel_persisted() {
+        let database = Database::open_in_memory().unwrap();
+        let mut config = GuildsConfig::load(database.clone()).unwrap();
+        assert!(config.add_config_role(1, 10).unwrap());
+        assert!(!config.add_config_role(1, 10).unwrap());
+        assert!(config.add_config_role(1, 11).unwrap());
+        assert!(config.remove_config_role(1, 11).unwrap());
+        assert!(!config.remove_config_role(2, 11).unwrap());
+        config.set_audit_channel(1, Some(20)).unwrap();
+        let config = GuildsConfig::load(database).unwrap();
+        assert_eq!(config.get_config_roles(1), vec![10]);
+        assert_eq!(config.get_audit_channel(1), Some(20));
+        assert_eq!(config.get_prefix(1), "-");
+    }
+
+    #[test]
+    pub fn users_config_persisted() {
+        let database = Database::open_in_memory().unwrap();
+        let mut config = UsersConfig::load(database.clone()).unwrap();
+        config.set_timezone(1, Some(Tz::Asia__Tokyo)).unwrap();
+        config.set_timezone(2, Some(Tz::Europe__Paris)).unwrap();
+        config.set_timezone(2, None).unwrap();
+        let config = UsersConfig::load(database).unwrap();
+        assert_eq!(config.get_timezone(1), Some(Tz::Asia__Tokyo));
+        assert_eq!(config.get_timezone(2), None);
+    }
+
+    #[test]
+    pub fn language_overrides_persisted() {
+        let database = Database::open_in_memory().unwrap();
+        let mut users = UsersConfig::load(database.clone()).unwrap();
+        users.set_timezone(1, Some(Tz::Asia__Tokyo)).unwrap();
+        users.set_language(1, Some(Language::French)).unwrap();
+        let mut channels = ChannelsConfig::load(database.clone()).unwrap();
+        channels.set_language(10, 100, Some(Language::French)).unwrap();
+        channels.set_language(11, 100, Some(Language::French)).unwrap();
+        channels.set_language(11, 100, None).unwrap();
+        let users = UsersConfig::load(database.clone()).unwrap();
+        assert_eq!(users.get_timezone(1), Some(Tz::Asia__Tokyo));
+        assert_eq!(users.get_language(1), Some(Language::French));
+        let channels = ChannelsConfig::load(database).unwrap();
+        assert_eq!(channels.get_language(10), Some(Language::French));
+        assert_eq!(channels.get_language(11), None);
+    }
+}
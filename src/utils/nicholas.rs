@@ -0,0 +1,247 @@
+use std::collections::{BTreeSet, HashMap};
+use std::str::FromStr;
+
+use rusqlite::{Connection, params};
+
+use crate::constants::NICHOLAS_TRAVELER_SIZE_CYCLE;
+use crate::enums::Language;
+use crate::utils::{CSVRecord, import_legacy_csv};
+use crate::utils::database::Database;
+
+const LEGACY_NICHOLAS_TRACKER_PATH: &str = "nicholas-tracker.csv";
+
+type UserRawId = u64;
+
+/// Weeks a user handed in Nicholas' item, numbered from the start of the rotation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NicholasRecord {
+    pub weeks: BTreeSet<i64>,
+    pub reminder: bool,
+    /// Language of the reminder DMs, taken from the server the reminder was enabled in.
+    pub language: Language,
+    /// Last week a reminder was sent, so restarts don't send it twice.
+    pub reminded: Option<i64>,
+}
+
+impl NicholasRecord {
+    fn new(language: Language) -> Self {
+        Self { weeks: BTreeSet::new(), reminder: false, language, reminded: None }
+    }
+
+    fn from_record(record: &CSVRecord) -> Option<(UserRawId, Self)> {
+        let user = u64::from_str(record.first()?).ok()?;
+        let reminder = record.get(1)? == "true";
+        let language = record.get(2)?.parse::<Language>().ok()?;
+        let reminded = record.get(3).and_then(|week| i64::from_str(week).ok());
+        let weeks = record.get(4)
+            .map(|weeks| weeks.split('|').filter_map(|week| i64::from_str(week).ok()).collect())
+            .unwrap_or_default();
+        Some((user, Self { weeks, reminder, language, reminded }))
+    }
+
+    /// `0` when the user is already tracked, whose weeks are then left untouched.
+    fn insert(&self, connection: &Connection, user: UserRawId) -> rusqlite::Result<usize> {
+        let inserted = connection.execute(
+            "INSERT OR IGNORE INTO nicholas_tracker (user, reminder, language, reminded) VALUES (?1, ?2, ?3, ?4)",
+            params![user, self.reminder, self.language.to_string(), self.reminded],
+        )?;
+        if inserted > 0 {
+            for week in &self.weeks {
+                connection.execute("INSERT OR IGNORE INTO nicholas_week (user, week) VALUES (?1, ?2)", params![user, week])?;
+            }
+        }
+        Ok(inserted)
+    }
+
+    pub fn is_done(&self, week: i64) -> bool {
+        self.weeks.contains(&week)
+    }
+
+    pub fn needs_reminder(&self, week: i64) -> bool {
+        self.reminder && !self.is_done(week) && self.reminded != Some(week)
+    }
+
+    /// Consecutive weeks handed in up to `week`, which doesn't break the streak while it's still running.
+    pub fn current_streak(&self, week: i64) -> usize {
+        let last = if self.is_done(week) { week } else { week - 1 };
+        (0..).take_while(|offset| self.is_done(last - offset)).count()
+    }
+
+    pub fn best_streak(&self) -> usize {
+        let mut best = 0;
+        let mut streak = 0;
+        let mut previous = None;
+        for week in &self.weeks {
+            streak = if previous == Some(week - 1) { streak + 1 } else { 1 };
+            best = best.max(streak);
+            previous = Some(*week);
+        }
+        best
+    }
+
+    /// Distinct gifts of the 137 weeks cycle handed in at least once.
+    pub fn cycle_collected(&self) -> usize {
+        self.weeks.iter()
+            .map(|week| week.rem_euclid(NICHOLAS_TRAVELER_SIZE_CYCLE))
+            .collect::<BTreeSet<_>>()
+            .len()
+    }
+}
+
+/// Weeks handed in by the users, kept in memory and written through to the database.
+#[derive(Debug)]
+pub struct NicholasTracker {
+    database: Database,
+    users: HashMap<UserRawId, NicholasRecord>,
+}
+
+impl NicholasTracker {
+    pub fn load(database: Database) -> rusqlite::Result<Self> {
+        let mut users: HashMap<_, _> = database.connection()
+            .prepare("SELECT user, reminder, language, reminded FROM nicholas_tracker")?
+            .query_map([], |row| {
+                let language: String = row.get(2)?;
+                let record = NicholasRecord {
+                    weeks: BTreeSet::new(),
+                    reminder: row.get(1)?,
+                    language: language.parse().unwrap_or(Language::English),
+                    reminded: row.get(3)?,
+                };
+                Ok((row.get(0)?, record))
+            })?
+            .collect::<rusqlite::Result<_>>()?;
+        let weeks = database.connection()
+            .prepare("SELECT user, week FROM nicholas_week")?
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<rusqlite::Result<Vec<(UserRawId, i64)>>>()?;
+        for (user, week) in weeks {
+            if let Some(record) = users.get_mut(&user) {
+                record.weeks.insert(week);
+            }
+        }
+        Ok(Self { database, users })
+    }
+
+    /// Same as [`crate::utils::GuildsConfig::import_csv`] for the tracker's file.
+    pub fn import_csv(database: &Database) -> rusqlite::Result<()> {
+        import_legacy_csv(database, LEGACY_NICHOLAS_TRACKER_PATH, |transaction, record| {
+            NicholasRecord::from_record(record)
+                .map(|(user, record)| record.insert(transaction, user))
+                .transpose()
+        })
+    }
+
+    pub fn get(&self, user: UserRawId) -> Option<&NicholasRecord> {
+        self.users.get(&user)
+    }
+
+    /// `false` when the week was already marked done.
+    pub fn mark_done(&mut self, user: UserRawId, week: i64, language: Language) -> rusqlite::Result<bool> {
+        let added = {
+            let mut connection = self.database.connection();
+            let transaction = connection.transaction()?;
+            NicholasRecord::new(language).insert(&transaction, user)?;
+            let added = transaction.execute(
+                "INSERT OR IGNORE INTO nicholas_week (user, week) VALUES (?1, ?2)",
+                params![user, week],
+            )? > 0;
+            transaction.commit()?;
+            added
+        };
+        self.users.entry(user)
+            .or_insert_with(|| NicholasRecord::new(language))
+            .weeks.insert(week);
+        Ok(added)
+    }
+
+    pub fn set_reminder(&mut self, user: UserRawId, reminder: bool, language: Language) -> rusqlite::Result<()> {
+        self.database.connection().execute(
+            "INSERT INTO nicholas_tracker (user, reminder, language) VALUES (?1, ?2, ?3)
+             ON CONFLICT (user) DO UPDATE SET reminder = excluded.reminder, language = excluded.language",
+            params![user, reminder, language.to_string()],
+        )?;
+        let record = self.users.entry(user).or_insert_with(|| NicholasRecord::new(language));
+        record.reminder = reminder;
+        record.language = language;
+        Ok(())
+    }
+
+    /// Users to remind for `week`, already marked as reminded.
+    pub fn take_reminders(&mut self, week: i64) -> rusqlite::Result<Vec<(UserRawId, Language)>> {
+        let due: Vec<_> = self.users.iter()
+            .filter(|(_, record)| record.needs_reminder(week))
+            .map(|(user, record)| (*user, record.language))
+            .collect();
+        if !due.is_empty() {
+            let mut connection = self.database.connection();
+            let transaction = connection.transaction()?;
+            for (user, _) in &due {
+                transaction.execute("UPDATE nicholas_tracker SET reminded = ?2 WHERE user = ?1", params![user, week])?;
+            }
+            transaction.commit()?;
+        }
+        for (user, _) in &due {
+            if let Some(record) = self.users.get_mut(user) {
+                record.reminded = Some(week);
+            }
+        }
+        Ok(due)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::enums::Language;
+    use crate::utils::database::Database;
+    use crate::utils::nicholas::{NicholasRecord, NicholasTracker};
+
+    fn record(weeks: &[i64]) -> NicholasRecord {
+        let mut record = NicholasRecord::new(Language::English);
+        record.weeks = weeks.iter().copied().collect();
+        record
+    }
+
+    #[test]
+    pub fn streaks_and_cycle() {
+        let record = record(&[3, 4, 5, 8, 9, 140]);
+        assert_eq!(record.best_streak(), 3);
+        assert_eq!(record.current_streak(9), 2);
+        assert_eq!(record.current_streak(10), 2);
+        assert_eq!(record.current_streak(11), 0);
+        assert_eq!(record.cycle_collected(), 5);
+    }
+
+    #[test]
+    pub fn tracker_persisted() {
+        let database = Database::open_in_memory().unwrap();
+        let legacy = ["42", "true", "fr-FR", "9", "3|4|5"].iter().map(|field| field.to_string()).collect();
+        let (user, imported) = NicholasRecord::from_record(&legacy).unwrap();
+        assert_eq!(imported.insert(&database.connection(), user).unwrap(), 1);
+        let mut tracker = NicholasTracker::load(database.clone()).unwrap();
+        assert_eq!(tracker.get(42), Some(&imported));
+        assert!(tracker.mark_done(42, 6, Language::English).unwrap());
+        assert!(!tracker.mark_done(42, 6, Language::English).unwrap());
+        assert!(tracker.mark_done(7, 6, Language::English).unwrap());
+        tracker.set_reminder(7, true, Language::French).unwrap();
+        let mut reminders = tracker.take_reminders(10).unwrap();
+        reminders.sort();
+        assert_eq!(reminders, vec![(7, Language::French), (42, Language::French)]);
+        // an import never overwrites what changed since
+        assert_eq!(imported.insert(&database.connection(), user).unwrap(), 0);
+        let mut tracker = NicholasTracker::load(database).unwrap();
+        assert_eq!(tracker.get(42).unwrap().weeks.len(), 4);
+        assert_eq!(tracker.get(7).unwrap().language, Language::French);
+        assert!(tracker.take_reminders(10).unwrap().is_empty());
+    }
+
+    #[test]
+    pub fn reminded_once_per_week() {
+        let mut record = record(&[10]);
+        assert!(!record.needs_reminder(11));
+        record.reminder = true;
+        assert!(!record.needs_reminder(10));
+        assert!(record.needs_reminder(11));
+        record.reminded = Some(11);
+        assert!(!record.needs_reminder(11));
+    }
+}
@@ -0,0 +1,147 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::{Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+use tracing::warn;
+
+/// Who shares the uses of a rate limited command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RateLimitScope {
+    User,
+    Channel,
+}
+
+/// `uses` of a command allowed per `window`, counted per user or per channel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimit {
+    pub uses: u32,
+    pub window: Duration,
+    pub scope: RateLimitScope,
+}
+
+/// Parses `<uses>/<seconds>/<user|channel>`, the scope defaults to the user.
+impl FromStr for RateLimit {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.trim().split('/');
+        let uses = parts.next().and_then(|uses| uses.trim().parse::<u32>().ok()).filter(|uses| *uses > 0)
+            .ok_or_else(|| format!("'{}' lacks a number of uses", s))?;
+        let seconds = parts.next().and_then(|seconds| seconds.trim().trim_end_matches('s').parse::<u64>().ok()).filter(|seconds| *seconds > 0)
+            .ok_or_else(|| format!("'{}' lacks a window in seconds", s))?;
+        let scope = match parts.next().map(|scope| scope.trim().to_lowercase()).as_deref() {
+            None | Some("user") => RateLimitScope::User,
+            Some("channel") => RateLimitScope::Channel,
+            Some(scope) => return Err(format!("'{}' isn't a scope, expected user or channel", scope)),
+        };
+        Ok(Self { uses, window: Duration::from_secs(seconds), scope })
+    }
+}
+
+/// A command refused until the window of its bucket ends.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimited {
+    pub retry_after: Duration,
+    /// Only the first refusal of a window is answered, the others are ignored.
+    pub first: bool,
+}
+
+#[derive(Debug)]
+struct Bucket {
+    end: Instant,
+    uses: u32,
+    refused: bool,
+}
+
+/// Counts the uses of the limited commands in fixed windows.
+#[derive(Debug, Default)]
+pub struct RateLimiter {
+    limits: HashMap<String, RateLimit>,
+    // the commands run under the read lock of the bot datas
+    buckets: Mutex<HashMap<(String, RateLimitScope, u64), Bucket>>,
+}
+
+impl RateLimiter {
+    pub fn new(limits: HashMap<String, RateLimit>) -> Self {
+        Self { limits, buckets: Mutex::default() }
+    }
+
+    /// Reads `command=<uses>/<seconds>/<user|channel>` entries separated by commas, skipping the invalid ones.
+    pub fn parse(config: &str) -> Self {
+        let limits = config.split(',')
+            .filter(|entry| !entry.trim().is_empty())
+            .filter_map(|entry| {
+                let parsed = entry.split_once('=')
+                    .ok_or_else(|| format!("'{}' lacks a command", entry))
+                    .and_then(|(command, limit)| Ok((command.trim().to_lowercase(), limit.parse::<RateLimit>()?)));
+                if let Err(why) = &parsed {
+                    warn!("Ignoring rate limit {}", why);
+                }
+                parsed.ok()
+            })
+            .collect();
+        Self::new(limits)
+    }
+
+    pub fn get(&self, command: &str) -> Option<&RateLimit> {
+        self.limits.get(command)
+    }
+
+    /// Counts a use of `command`, refused once its bucket used up the window.
+    pub fn hit(&self, command: &str, user: u64, channel: u64, now: Instant) -> Result<(), RateLimited> {
+        let limit = match self.limits.get(command) {
+            Some(limit) => limit,
+            None => return Ok(()),
+        };
+        let id = match limit.scope {
+            RateLimitScope::User => user,
+            RateLimitScope::Channel => channel,
+        };
+        let mut buckets = self.buckets.lock().unwrap_or_else(PoisonError::into_inner);
+        buckets.retain(|_, bucket| bucket.end > now);
+        let bucket = buckets.entry((command.to_string(), limit.scope, id))
+            .or_insert_with(|| Bucket { end: now + limit.window, uses: 0, refused: false });
+        if bucket.uses < limit.uses {
+            bucket.uses += 1;
+            return Ok(());
+        }
+        let first = !bucket.refused;
+        bucket.refused = true;
+        Err(RateLimited { retry_after: bucket.end - now, first })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::{Duration, Instant};
+
+    use crate::utils::ratelimit::{RateLimit, RateLimited, RateLimiter, RateLimitScope};
+
+    #[test]
+    pub fn limits_parsed() {
+        let limiter = RateLimiter::parse("skill=3/30/user, menu = 1/60s/channel,zq=oops,ping=2/10");
+        assert_eq!(limiter.get("skill"), Some(&RateLimit { uses: 3, window: Duration::from_secs(30), scope: RateLimitScope::User }));
+        assert_eq!(limiter.get("menu"), Some(&RateLimit { uses: 1, window: Duration::from_secs(60), scope: RateLimitScope::Channel }));
+        assert_eq!(limiter.get("ping").map(|limit| limit.scope), Some(RateLimitScope::User));
+        assert_eq!(limiter.get("zq"), None);
+    }
+
+    #[test]
+    pub fn uses_refused_until_the_window_ends() {
+        let limiter = RateLimiter::parse("skill=2/30/user,menu=1/60/channel");
+        let start = Instant::now();
+        assert!(limiter.hit("skill", 1, 10, start).is_ok());
+        assert!(limiter.hit("skill", 1, 11, start).is_ok());
+        assert_eq!(limiter.hit("skill", 1, 10, start + Duration::from_secs(10)),
+                   Err(RateLimited { retry_after: Duration::from_secs(20), first: true }));
+        assert!(!limiter.hit("skill", 1, 10, start + Duration::from_secs(11)).unwrap_err().first);
+        assert!(limiter.hit("skill", 2, 10, start).is_ok());
+        assert!(limiter.hit("skill", 1, 10, start + Duration::from_secs(30)).is_ok());
+
+        assert!(limiter.hit("menu", 1, 10, start).is_ok());
+        assert!(limiter.hit("menu", 2, 10, start).is_err());
+        assert!(limiter.hit("menu", 2, 11, start).is_ok());
+        assert!(limiter.hit("zq", 1, 10, start).is_ok());
+    }
+}
@@ -0,0 +1,256 @@
+use std::fmt::Debug;
+
+use chrono::{Datelike, DateTime, Duration, NaiveDate, TimeZone, Utc};
+
+use crate::constants::{DAILY_RESET_HOUR, WEEKLY_RESET_HOUR};
+
+/// Source of the current instant, so reset computations can be tested against a fixed date.
+pub trait Clock: Debug + Send + Sync {
+    fn now(&self) -> DateTime<Utc>;
+}
+
+#[derive(Debug, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+#[derive(Debug)]
+pub struct FixedClock(pub DateTime<Utc>);
+
+impl Clock for FixedClock {
+    fn now(&self) -> DateTime<Utc> {
+        self.0
+    }
+}
+
+/// Daily activities reset every day at 16:00 UTC, weekly ones every Monday at 15:00 UTC.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum ResetCycle {
+    Daily,
+    Weekly,
+}
+
+impl ResetCycle {
+    pub fn period(&self) -> Duration {
+        match self {
+            ResetCycle::Daily => Duration::days(1),
+            ResetCycle::Weekly => Duration::weeks(1),
+        }
+    }
+
+    /// Reset instant of the period containing `date`, e.g. the Monday 15:00 of that week.
+    pub fn reset_of(&self, date: NaiveDate) -> DateTime<Utc> {
+        let (date, hour) = match self {
+            ResetCycle::Daily => (date, DAILY_RESET_HOUR),
+            ResetCycle::Weekly => (date - Duration::days(date.weekday().num_days_from_monday() as i64), WEEKLY_RESET_HOUR),
+        };
+        Utc.from_utc_datetime(&date.and_hms_opt(hour, 0, 0).unwrap())
+    }
+
+    /// Last reset that happened at or before `at`.
+    pub fn previous(&self, at: DateTime<Utc>) -> DateTime<Utc> {
+        let candidate = self.reset_of(at.date_naive());
+        if candidate > at {
+            candidate - self.period()
+        } else {
+            candidate
+        }
+    }
+
+    /// First reset strictly after `at`.
+    pub fn next(&self, at: DateTime<Utc>) -> DateTime<Utc> {
+        self.previous(at) + self.period()
+    }
+
+    /// Number of whole periods between the reset of `origin` and the period containing `at`,
+    /// negative when `at` is before `origin`.
+    pub fn periods_since(&self, origin: NaiveDate, at: DateTime<Utc>) -> i64 {
+        let elapsed = self.previous(at).signed_duration_since(self.reset_of(origin));
+        elapsed.num_seconds().div_euclid(self.period().num_seconds())
+    }
+
+    pub fn previous_from(&self, clock: &dyn Clock) -> DateTime<Utc> {
+        self.previous(clock.now())
+    }
+
+    pub fn next_from(&self, clock: &dyn Clock) -> DateTime<Utc> {
+        self.next(clock.now())
+    }
+}
+
+/// A fixed list of entries cycling at every reset, starting with the entry 0 at `start`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Rotation {
+    pub cycle: ResetCycle,
+    pub start: (i32, u32, u32),
+    pub size: i64,
+}
+
+impl Rotation {
+    pub const fn daily(start: (i32, u32, u32), size: i64) -> Self {
+        Self { cycle: ResetCycle::Daily, start, size }
+    }
+
+    pub const fn weekly(start: (i32, u32, u32), size: i64) -> Self {
+        Self { cycle: ResetCycle::Weekly, start, size }
+    }
+
+    fn start_date(&self) -> NaiveDate {
+        NaiveDate::from_ymd_opt(self.start.0, self.start.1, self.start.2).unwrap()
+    }
+
+    /// Number of resets since `start`, keeping counting across cycles.
+    pub fn period_at(&self, at: DateTime<Utc>) -> i64 {
+        self.cycle.periods_since(self.start_date(), at)
+    }
+
+    /// Index of the entry active at `at`.
+    pub fn index_at(&self, at: DateTime<Utc>) -> i64 {
+        self.period_at(at).rem_euclid(self.size)
+    }
+
+    /// Start of the first period where `index` is active, the current one included.
+    pub fn occurrence(&self, index: i64, at: DateTime<Utc>) -> DateTime<Utc> {
+        let offset = (index - self.index_at(at)).rem_euclid(self.size);
+        self.cycle.previous(at) + self.cycle.period() * offset as i32
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::{Datelike, DateTime, Duration, NaiveDate, TimeZone, Timelike, Utc, Weekday};
+
+    use crate::constants::{BONUS_EVENT_PVE_SIZE_CYCLE, BONUS_EVENT_START, NICHOLAS_TRAVELER_SIZE_CYCLE, NICHOLAS_TRAVELER_START, PRE_SEARING, SHINING_BLADE_BOUNTY, VANGUARD_QUEST, ZAISHEN_MISSION_SIZE_CYCLE, ZAISHEN_MISSION_START};
+    use crate::utils::reset::{Clock, FixedClock, ResetCycle, Rotation};
+
+    fn utc(y: i32, m: u32, d: u32, h: u32, min: u32, s: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(y, m, d, h, min, s).unwrap()
+    }
+
+    #[test]
+    pub fn daily_around_reset() {
+        let daily = ResetCycle::Daily;
+        assert_eq!(daily.previous(utc(2020, 9, 7, 15, 59, 59)), utc(2020, 9, 6, 16, 0, 0));
+        assert_eq!(daily.next(utc(2020, 9, 7, 15, 59, 59)), utc(2020, 9, 7, 16, 0, 0));
+        assert_eq!(daily.previous(utc(2020, 9, 7, 16, 0, 0)), utc(2020, 9, 7, 16, 0, 0));
+        assert_eq!(daily.next(utc(2020, 9, 7, 16, 0, 0)), utc(2020, 9, 8, 16, 0, 0));
+        assert_eq!(daily.previous(utc(2020, 9, 7, 16, 0, 1)), utc(2020, 9, 7, 16, 0, 0));
+        assert_eq!(daily.previous(utc(2020, 9, 7, 0, 0, 0)), utc(2020, 9, 6, 16, 0, 0));
+        assert_eq!(daily.next(utc(2020, 9, 7, 23, 59, 59)), utc(2020, 9, 8, 16, 0, 0));
+    }
+
+    #[test]
+    pub fn daily_across_month_year_and_leap_day() {
+        let daily = ResetCycle::Daily;
+        assert_eq!(daily.next(utc(2020, 2, 28, 17, 0, 0)), utc(2020, 2, 29, 16, 0, 0));
+        assert_eq!(daily.next(utc(2020, 2, 29, 17, 0, 0)), utc(2020, 3, 1, 16, 0, 0));
+        assert_eq!(daily.next(utc(2020, 12, 31, 16, 0, 0)), utc(2021, 1, 1, 16, 0, 0));
+        assert_eq!(daily.previous(utc(2021, 1, 1, 10, 0, 0)), utc(2020, 12, 31, 16, 0, 0));
+    }
+
+    #[test]
+    pub fn weekly_around_reset() {
+        let weekly = ResetCycle::Weekly;
+        // 2020-09-07 is a Monday
+        assert_eq!(weekly.previous(utc(2020, 9, 6, 23, 59, 59)), utc(2020, 8, 31, 15, 0, 0));
+        assert_eq!(weekly.next(utc(2020, 9, 6, 23, 59, 59)), utc(2020, 9, 7, 15, 0, 0));
+        assert_eq!(weekly.previous(utc(2020, 9, 7, 0, 0, 0)), utc(2020, 8, 31, 15, 0, 0));
+        assert_eq!(weekly.next(utc(2020, 9, 7, 0, 0, 0)), utc(2020, 9, 7, 15, 0, 0));
+        assert_eq!(weekly.next(utc(2020, 9, 7, 14, 59, 59)), utc(2020, 9, 7, 15, 0, 0));
+        assert_eq!(weekly.previous(utc(2020, 9, 7, 15, 0, 0)), utc(2020, 9, 7, 15, 0, 0));
+        assert_eq!(weekly.next(utc(2020, 9, 7, 15, 0, 0)), utc(2020, 9, 14, 15, 0, 0));
+        assert_eq!(weekly.next(utc(2020, 9, 7, 15, 0, 1)), utc(2020, 9, 14, 15, 0, 0));
+        assert_eq!(weekly.previous(utc(2020, 9, 8, 12, 0, 0)), utc(2020, 9, 7, 15, 0, 0));
+        assert_eq!(weekly.next(utc(2020, 12, 30, 12, 0, 0)), utc(2021, 1, 4, 15, 0, 0));
+    }
+
+    #[test]
+    pub fn every_minute_of_a_fortnight_lies_between_its_resets() {
+        let from = utc(2020, 2, 20, 0, 0, 0);
+        for cycle in [ResetCycle::Daily, ResetCycle::Weekly] {
+            for minute in 0..(14 * 24 * 60) {
+                let at = from + Duration::minutes(minute);
+                let previous = cycle.previous(at);
+                let next = cycle.next(at);
+                assert!(previous <= at && at < next, "{:?} {}", cycle, at);
+                assert_eq!(next - previous, cycle.period());
+                assert_eq!(previous.minute(), 0);
+                match cycle {
+                    ResetCycle::Daily => assert_eq!(previous.hour(), 16),
+                    ResetCycle::Weekly => {
+                        assert_eq!(previous.hour(), 15);
+                        assert_eq!(previous.weekday(), Weekday::Mon);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    pub fn periods_since_origin() {
+        let origin = NaiveDate::from_ymd_opt(2020, 9, 7).unwrap();
+        let daily = ResetCycle::Daily;
+        assert_eq!(daily.periods_since(origin, utc(2020, 9, 7, 16, 0, 0)), 0);
+        assert_eq!(daily.periods_since(origin, utc(2020, 9, 8, 15, 59, 59)), 0);
+        assert_eq!(daily.periods_since(origin, utc(2020, 9, 8, 16, 0, 0)), 1);
+        assert_eq!(daily.periods_since(origin, utc(2020, 9, 7, 15, 59, 59)), -1);
+        let weekly = ResetCycle::Weekly;
+        assert_eq!(weekly.periods_since(origin, utc(2020, 9, 7, 15, 0, 0)), 0);
+        assert_eq!(weekly.periods_since(origin, utc(2020, 9, 14, 14, 59, 59)), 0);
+        assert_eq!(weekly.periods_since(origin, utc(2020, 9, 14, 15, 0, 0)), 1);
+        assert_eq!(weekly.periods_since(origin, utc(2020, 9, 7, 14, 59, 59)), -1);
+    }
+
+    #[test]
+    pub fn rotation_indices() {
+        let mission = Rotation::daily(ZAISHEN_MISSION_START, ZAISHEN_MISSION_SIZE_CYCLE);
+        assert_eq!(mission.index_at(utc(2011, 3, 3, 16, 0, 0)), 0);
+        assert_eq!(mission.index_at(utc(2011, 3, 3, 15, 59, 59)), ZAISHEN_MISSION_SIZE_CYCLE - 1);
+        assert_eq!(mission.index_at(utc(2011, 3, 4, 16, 0, 0)), 1);
+        // Augury Rock came back on 2020-03-27
+        assert_eq!(mission.index_at(utc(2020, 3, 27, 20, 0, 0)), 0);
+        let nicholas = Rotation::weekly(NICHOLAS_TRAVELER_START, NICHOLAS_TRAVELER_SIZE_CYCLE);
+        // Drake Kabob on the week of 2020-09-07
+        assert_eq!(nicholas.index_at(utc(2020, 9, 7, 15, 0, 0)), 0);
+        assert_eq!(nicholas.index_at(utc(2020, 9, 7, 14, 59, 59)), NICHOLAS_TRAVELER_SIZE_CYCLE - 1);
+        assert_eq!(nicholas.index_at(utc(2020, 9, 10, 12, 0, 0)), 0);
+        let bonus = Rotation::weekly(BONUS_EVENT_START, BONUS_EVENT_PVE_SIZE_CYCLE);
+        assert_eq!(bonus.index_at(utc(2020, 8, 17, 14, 59, 59)), 0);
+        assert_eq!(bonus.index_at(utc(2020, 8, 17, 15, 0, 0)), 1);
+    }
+
+    #[test]
+    pub fn daily_quest_indices() {
+        // Vanguard Annihilation: Bandits on 2011-03-03, Vanguard Rescue: Farmer Hamnet on 2020-09-07
+        assert_eq!(VANGUARD_QUEST.index_at(utc(2011, 3, 3, 16, 0, 0)), 0);
+        assert_eq!(VANGUARD_QUEST.index_at(utc(2020, 9, 7, 16, 0, 0)), 2);
+        // Wanted: Justiciar Kasandra on 2010-06-08, Wanted: Cerris on 2020-09-07
+        assert_eq!(SHINING_BLADE_BOUNTY.index_at(utc(2010, 6, 8, 16, 0, 0)), 0);
+        assert_eq!(SHINING_BLADE_BOUNTY.index_at(utc(2010, 6, 8, 15, 59, 59)), 20);
+        assert_eq!(SHINING_BLADE_BOUNTY.index_at(utc(2020, 9, 7, 16, 0, 0)), 6);
+        // 5 Grawl Necklaces on 2009-04-09, 5 Baked Husks on 2020-09-07
+        assert_eq!(PRE_SEARING.index_at(utc(2009, 4, 9, 16, 0, 0)), 0);
+        assert_eq!(PRE_SEARING.index_at(utc(2020, 9, 7, 16, 0, 0)), 9);
+    }
+
+    #[test]
+    pub fn rotation_occurrences() {
+        let nicholas = Rotation::weekly(NICHOLAS_TRAVELER_START, NICHOLAS_TRAVELER_SIZE_CYCLE);
+        assert_eq!(nicholas.occurrence(0, utc(2020, 9, 10, 12, 0, 0)), utc(2020, 9, 7, 15, 0, 0));
+        assert_eq!(nicholas.occurrence(1, utc(2020, 9, 10, 12, 0, 0)), utc(2020, 9, 14, 15, 0, 0));
+        assert_eq!(nicholas.occurrence(0, utc(2020, 9, 14, 15, 0, 0)), utc(2020, 9, 7, 15, 0, 0) + Duration::weeks(NICHOLAS_TRAVELER_SIZE_CYCLE));
+    }
+
+    #[test]
+    pub fn clock_driven_resets() {
+        let clock = FixedClock(utc(2020, 9, 7, 14, 0, 0));
+        assert_eq!(clock.now(), utc(2020, 9, 7, 14, 0, 0));
+        assert_eq!(ResetCycle::Daily.next_from(&clock), utc(2020, 9, 7, 16, 0, 0));
+        assert_eq!(ResetCycle::Weekly.next_from(&clock), utc(2020, 9, 7, 15, 0, 0));
+        assert_eq!(ResetCycle::Weekly.previous_from(&clock), utc(2020, 8, 31, 15, 0, 0));
+    }
+}
@@ -0,0 +1,145 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use rusqlite::params;
+
+use crate::utils::database::Database;
+
+type GuildRawId = u64;
+
+/// Why a rule refused a command.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RuleRefusal {
+    Disabled,
+    /// Channels the command is limited to.
+    Channels(Vec<u64>),
+}
+
+/// Commands a guild turned off and channels it limited commands to.
+/// A rule targets a command name, a group name or `all`.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct GuildRules {
+    pub disabled: BTreeSet<String>,
+    pub channels: BTreeMap<String, BTreeSet<u64>>,
+}
+
+impl GuildRules {
+    /// `targets` go from the most to the least specific, the channels of the most specific one limited apply.
+    pub fn check(&self, targets: &[&str], channel: u64) -> Result<(), RuleRefusal> {
+        if targets.iter().any(|target| self.disabled.contains(*target)) {
+            return Err(RuleRefusal::Disabled);
+        }
+        match targets.iter().find_map(|target| self.channels.get(*target)) {
+            Some(channels) if !channels.contains(&channel) => Err(RuleRefusal::Channels(channels.iter().copied().collect())),
+            _ => Ok(()),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.disabled.is_empty() && self.channels.is_empty()
+    }
+}
+
+/// Rules of every guild, kept in memory and written through to the database.
+#[derive(Debug)]
+pub struct CommandRules {
+    database: Database,
+    guilds: HashMap<GuildRawId, GuildRules>,
+}
+
+impl CommandRules {
+    pub fn load(database: Database) -> rusqlite::Result<Self> {
+        let mut guilds: HashMap<GuildRawId, GuildRules> = HashMap::new();
+        {
+            let connection = database.connection();
+            let disabled = connection
+                .prepare("SELECT guild, target FROM command_disabled")?
+                .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+                .collect::<rusqlite::Result<Vec<(GuildRawId, String)>>>()?;
+            for (guild, target) in disabled {
+                guilds.entry(guild).or_default().disabled.insert(target);
+            }
+            let channels = connection
+                .prepare("SELECT guild, target, channel FROM command_channel")?
+                .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?
+                .collect::<rusqlite::Result<Vec<(GuildRawId, String, u64)>>>()?;
+            for (guild, target, channel) in channels {
+                guilds.entry(guild).or_default().channels.entry(target).or_default().insert(channel);
+            }
+        }
+        Ok(Self { database, guilds })
+    }
+
+    pub fn get(&self, guild: GuildRawId) -> Option<&GuildRules> {
+        self.guilds.get(&guild)
+    }
+
+    pub fn check(&self, guild: GuildRawId, targets: &[&str], channel: u64) -> Result<(), RuleRefusal> {
+        self.guilds.get(&guild)
+            .map(|rules| rules.check(targets, channel))
+            .unwrap_or(Ok(()))
+    }
+
+    /// `false` when the target already was in that state.
+    pub fn set_disabled(&mut self, guild: GuildRawId, target: &str, disabled: bool) -> rusqlite::Result<bool> {
+        let sql = if disabled {
+            "INSERT OR IGNORE INTO command_disabled (guild, target) VALUES (?1, ?2)"
+        } else {
+            "DELETE FROM command_disabled WHERE guild = ?1 AND target = ?2"
+        };
+        let changed = self.database.connection().execute(sql, params![guild, target])? > 0;
+        let rules = self.guilds.entry(guild).or_default();
+        if disabled {
+            rules.disabled.insert(target.to_string());
+        } else {
+            rules.disabled.remove(target);
+        }
+        Ok(changed)
+    }
+
+    /// Limits the target to `channels`, no channel lifts the limit.
+    pub fn set_channels(&mut self, guild: GuildRawId, target: &str, channels: BTreeSet<u64>) -> rusqlite::Result<()> {
+        {
+            let mut connection = self.database.connection();
+            let transaction = connection.transaction()?;
+            transaction.execute("DELETE FROM command_channel WHERE guild = ?1 AND target = ?2", params![guild, target])?;
+            for channel in &channels {
+                transaction.execute(
+                    "INSERT INTO command_channel (guild, target, channel) VALUES (?1, ?2, ?3)",
+                    params![guild, target, channel],
+                )?;
+            }
+            transaction.commit()?;
+        }
+        let rules = self.guilds.entry(guild).or_default();
+        if channels.is_empty() {
+            rules.channels.remove(target);
+        } else {
+            rules.channels.insert(target.to_string(), channels);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::utils::database::Database;
+    use crate::utils::rules::{CommandRules, RuleRefusal};
+
+    #[test]
+    pub fn rules_checked_from_the_most_specific() {
+        let database = Database::open_in_memory().unwrap();
+        let mut rules = CommandRules::load(database.clone()).unwrap();
+        rules.set_channels(1, "all", vec![10, 11].into_iter().collect()).unwrap();
+        rules.set_channels(1, "skill", vec![12].into_iter().collect()).unwrap();
+        assert!(rules.set_disabled(1, "menu", true).unwrap());
+        assert!(!rules.set_disabled(1, "menu", true).unwrap());
+
+        let rules = CommandRules::load(database).unwrap();
+        assert_eq!(rules.check(1, &["zq", "rotations", "all"], 10), Ok(()));
+        assert_eq!(rules.check(1, &["zq", "rotations", "all"], 12), Err(RuleRefusal::Channels(vec![10, 11])));
+        assert_eq!(rules.check(1, &["skill", "tools", "all"], 12), Ok(()));
+        assert_eq!(rules.check(1, &["skill", "tools", "all"], 10), Err(RuleRefusal::Channels(vec![12])));
+        assert_eq!(rules.check(1, &["menu", "tools", "all"], 10), Err(RuleRefusal::Disabled));
+        assert_eq!(rules.check(2, &["menu", "tools", "all"], 10), Ok(()));
+    }
+}
@@ -1,6 +1,6 @@
 use std::str::Chars;
 
-use crate::constants::STANDARD_DECODE;
+use crate::constants::{INVALID_VALUE, STANDARD_DECODE};
 use crate::enums::{AttributeType, ProfessionType};
 
 fn flip_binary_pad(binary: String) -> String {
@@ -29,58 +29,59 @@ fn unflip_binary(binary: String) -> u32 {
     num
 }
 
-fn string_from_n_chars(iter: &mut Chars, n: usize) -> String {
+fn string_from_n_chars(iter: &mut Chars, n: usize) -> Option<String> {
     let mut buffer = String::default();
     for _i in 0..n {
-        buffer.push(iter.next().unwrap());
+        buffer.push(iter.next()?);
     }
-    buffer
+    Some(buffer)
 }
 
-fn read_template_header(binary: &mut Chars) {
-    let _template_type = string_from_n_chars(binary, 4);
+fn read_template_header(binary: &mut Chars) -> Option<()> {
+    let _template_type = string_from_n_chars(binary, 4)?;
     // let template_type = unflip_binary(template_type);
-    let _version = string_from_n_chars(binary, 4);
+    let _version = string_from_n_chars(binary, 4)?;
     // let version = unflip_binary(version);
     // println!("template type: {}, version: {}", template_type, version);
+    Some(())
 }
 
-fn read_professions(binary: &mut Chars) -> (ProfessionType, ProfessionType) {
-    let profession_chunk_size = string_from_n_chars(binary, 2);
+fn read_professions(binary: &mut Chars) -> Option<(ProfessionType, ProfessionType)> {
+    let profession_chunk_size = string_from_n_chars(binary, 2)?;
     let profession_chunk_size = unflip_binary(profession_chunk_size) * 2 + 4;
-    let first_profession = string_from_n_chars(binary, profession_chunk_size as usize);
-    let second_profession = string_from_n_chars(binary, profession_chunk_size as usize);
+    let first_profession = string_from_n_chars(binary, profession_chunk_size as usize)?;
+    let second_profession = string_from_n_chars(binary, profession_chunk_size as usize)?;
     let first_profession = unflip_binary(first_profession);
     let second_profession = unflip_binary(second_profession);
-    (ProfessionType::from(first_profession), ProfessionType::from(second_profession))
+    Some((ProfessionType::from_id(first_profession)?, ProfessionType::from_id(second_profession)?))
 }
 
-fn read_attributes(binary: &mut Chars) -> Vec<(AttributeType, u32)> {
-    let count_attributes = string_from_n_chars(binary, 4);
+fn read_attributes(binary: &mut Chars) -> Option<Vec<(AttributeType, u32)>> {
+    let count_attributes = string_from_n_chars(binary, 4)?;
     let count_attributes = unflip_binary(count_attributes);
-    let attribute_chunk_size = string_from_n_chars(binary, 4);
+    let attribute_chunk_size = string_from_n_chars(binary, 4)?;
     let attribute_chunk_size = unflip_binary(attribute_chunk_size) + 4;
     let mut attributes = vec![];
     for _i in 0..count_attributes {
-        let attribute_id = string_from_n_chars(binary, attribute_chunk_size as usize);
+        let attribute_id = string_from_n_chars(binary, attribute_chunk_size as usize)?;
         let attribute_id = unflip_binary(attribute_id);
-        let attribute_points = string_from_n_chars(binary, 4);
+        let attribute_points = string_from_n_chars(binary, 4)?;
         let attribute_points = unflip_binary(attribute_points);
-        attributes.push((AttributeType::from(attribute_id), attribute_points));
+        attributes.push((AttributeType::from_id(attribute_id)?, attribute_points));
     }
-    attributes
+    Some(attributes)
 }
 
-fn read_skills(binary: &mut Chars) -> [u32; 8] {
-    let skill_chunk_size = string_from_n_chars(binary, 4);
+fn read_skills(binary: &mut Chars) -> Option<[u32; 8]> {
+    let skill_chunk_size = string_from_n_chars(binary, 4)?;
     let skill_chunk_size = unflip_binary(skill_chunk_size) + 8;
     let mut skills = [0u32; 8];
     for skill in skills.iter_mut() {
-        let skill_id = string_from_n_chars(binary, skill_chunk_size as usize);
+        let skill_id = string_from_n_chars(binary, skill_chunk_size as usize)?;
         let skill_id = unflip_binary(skill_id);
         *skill = skill_id;
     }
-    skills
+    Some(skills)
 }
 
 #[derive(Debug, PartialEq)]
@@ -91,31 +92,36 @@ pub struct SkillCodeRecord {
     pub skills: [u32; 8],
 }
 
-fn decode(code: String) -> String {
+/// `None` when the code holds a character outside of the base64 alphabet.
+fn decode(code: String) -> Option<String> {
     code
         .bytes()
-        .map(|x| STANDARD_DECODE[x as usize])
-        .map(|x| format!("{:0>6b}", x))
-        .map(flip_binary_pad)
-        .collect::<Vec<String>>().join("")
+        .map(|x| match STANDARD_DECODE[x as usize] {
+            INVALID_VALUE => None,
+            x => Some(format!("{:0>6b}", x)),
+        })
+        .map(|x| x.map(flip_binary_pad))
+        .collect::<Option<Vec<String>>>()
+        .map(|chunks| chunks.join(""))
 }
 
 pub struct SkillCodeParser;
 
 impl SkillCodeParser {
-    pub fn parse(skill_code: String) -> SkillCodeRecord {
-        let binary_code = decode(skill_code);
+    /// `None` when `skill_code` is not a valid build template code.
+    pub fn parse(skill_code: String) -> Option<SkillCodeRecord> {
+        let binary_code = decode(skill_code)?;
         let mut binary_code = binary_code.chars();
-        read_template_header(&mut binary_code);
-        let (primary, secondary) = read_professions(&mut binary_code);
-        let attributes = read_attributes(&mut binary_code);
-        let skills = read_skills(&mut binary_code);
-        SkillCodeRecord {
+        read_template_header(&mut binary_code)?;
+        let (primary, secondary) = read_professions(&mut binary_code)?;
+        let attributes = read_attributes(&mut binary_code)?;
+        let skills = read_skills(&mut binary_code)?;
+        Some(SkillCodeRecord {
             primary_profession: primary,
             secondary_profession: secondary,
             attributes,
             skills,
-        }
+        })
     }
 }
 
@@ -134,6 +140,7 @@ mod test {
     use crate::enums::AttributeType::*;
     use crate::enums::ProfessionType::*;
     use crate::utils::skill::{SkillCodeParser, SkillCodeRecord};
+    use crate::utils::database::Database;
     use crate::utils::SKillI18nStore;
 
     #[derive(Debug, PartialEq)]
@@ -161,18 +168,18 @@ mod test {
 
     #[test]
     pub fn full_skill_set() {
-        let datas = BotData::init();
+        let datas = BotData::init(Database::open_in_memory().unwrap());
         let code_skill = "OgdCoMzjyAYg7OiDDeBuQAA".to_string();
         let skills = vec!["Glyph of Sacrifice".to_string(), "Meteor Shower".to_string(), "Death's Charge".to_string(), "Star Burst".to_string(), "Lava Font".to_string(), "Flame Burst".to_string(), "Fire Attunement".to_string(), "Resurrection Signet".to_string()];
         let expected = SkillCodeRecordTranslated { primary_profession: Elementalist, secondary_profession: Assassin, attributes: vec![(FireMagic, 12), (EnergyStorage, 12)], skills };
-        let actual = SkillCodeParser::parse(code_skill);
+        let actual = SkillCodeParser::parse(code_skill).unwrap();
         let actual = record_to_translated(actual, &datas.skills);
         assert_eq!(actual, expected);
     }
 
     #[test]
     pub fn skill_holed_set() {
-        let datas = BotData::init();
+        let datas = BotData::init(Database::open_in_memory().unwrap());
         let code_skill = "OgdR8ZaCC3xmkUMCCAAAIVE".to_string();
         let skills = vec!["Dash".to_string(), "Death's Charge".to_string(), "\"You Move Like a Dwarf!\"".to_string(), "Light of Deldrimor".to_string(), "Unseen Fury".to_string(), "No Skill".to_string(), "No Skill".to_string(), "\"By Ural's Hammer!\"".to_string()];
         let expected = SkillCodeRecordTranslated {
@@ -181,14 +188,14 @@ mod test {
             attributes: vec![(ShadowArts, 12)],
             skills,
         };
-        let actual = SkillCodeParser::parse(code_skill);
+        let actual = SkillCodeParser::parse(code_skill).unwrap();
         let actual = record_to_translated(actual, &datas.skills);
         assert_eq!(expected, actual);
     }
 
     #[test]
     pub fn skill_seed_mindbender_lightdeldrimor() {
-        let datas = BotData::init();
+        let datas = BotData::init(Database::open_in_memory().unwrap());
         let code_skill = "OwYT4yXCZCgYtcZIHMlAAgUMeAA".to_string();
         let skills = vec!["Blessed Aura".to_string(), "Mindbender".to_string(), "Glyph of Renewal".to_string(), "Seed of Life".to_string(), "Blessed Signet".to_string(), "No Skill".to_string(), "Light of Deldrimor".to_string(), "Life Bond".to_string()];
         let expected = SkillCodeRecordTranslated {
@@ -197,12 +204,20 @@ mod test {
             attributes: vec![(SmitingPrayers, 9), (ProtectionPrayers, 9), (DivineFavor, 12)],
             skills,
         };
-        let actual = SkillCodeParser::parse(code_skill);
+        let actual = SkillCodeParser::parse(code_skill).unwrap();
         let actual = record_to_translated(actual, &datas.skills);
         assert_eq!(expected, actual);
     }
     //rajouter test skill pve lulu/kuku et co et inconnu
 
+    #[test]
+    pub fn invalid_codes_rejected() {
+        assert!(SkillCodeParser::parse("Healing Signe".to_string()).is_none());
+        assert!(SkillCodeParser::parse("Ogejk".to_string()).is_none());
+        assert!(SkillCodeParser::parse("Mindbender".to_string()).is_none());
+        assert!(SkillCodeParser::parse(String::new()).is_none());
+    }
+
     #[test]
     pub fn test_image_build() {
         let ids = [1043, 952, 2358, 2212, 1041, 0, 0, 2217];
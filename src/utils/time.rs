@@ -1,7 +1,11 @@
 use std::cmp::Ordering;
-use std::ops::Add;
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
 
-use chrono::{Date, Datelike, DateTime, Duration, Timelike, TimeZone, Utc};
+use chrono::{DateTime, TimeZone, Utc};
+use chrono_tz::Tz;
+
+use crate::constants::DATETIME_FORMAT;
 
 #[derive(Clone, PartialOrd, PartialEq, Eq, Ord)]
 pub enum DateTimeRangeComparison {
@@ -10,7 +14,7 @@ pub enum DateTimeRangeComparison {
     After = 2,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct DateTimeRange<T: TimeZone>(pub DateTime<T>, pub DateTime<T>);
 
 impl<T: TimeZone> DateTimeRange<T> {
@@ -41,27 +45,133 @@ pub fn get_time_left(from: DateTime<Utc>, since: DateTime<Utc>) -> (i64, i64, i6
     (days_left, hours_left, mins_left, secs_left)
 }
 
-pub fn get_next_week(now: DateTime<Utc>) -> Date<Utc> {
-    let weekday = now.weekday();
-    if weekday.num_days_from_monday() == 0 {
-        now.date()
-    } else {
-        now.add(Duration::days((7 - weekday.num_days_from_monday()) as i64)).date()
+/// Parses either an IANA zone name (`Europe/Paris`) or a fixed UTC offset in hours
+/// (`+2`, `UTC-5`) which is mapped onto the matching `Etc/GMT` zone.
+pub fn parse_timezone(s: &str) -> Result<Tz, String> {
+    let error = || format!("Could not convert '{}' into a known time zone", s);
+    let trimmed = s.trim();
+    if let Ok(tz) = trimmed.parse::<Tz>() {
+        return Ok(tz);
+    }
+    let offset = trimmed.strip_prefix("UTC")
+        .or_else(|| trimmed.strip_prefix("utc"))
+        .or_else(|| trimmed.strip_prefix("GMT"))
+        .unwrap_or(trimmed);
+    let offset = offset.parse::<i32>().map_err(|_| error())?;
+    // Etc/GMT zones use the POSIX convention, so the sign is inverted
+    let name = match offset {
+        0 => "Etc/GMT".to_string(),
+        o if o > 0 => format!("Etc/GMT-{}", o),
+        o => format!("Etc/GMT+{}", -o),
+    };
+    name.parse::<Tz>().map_err(|_| error())
+}
+
+pub fn format_datetime(date: &DateTime<Utc>, tz: &Tz) -> String {
+    date.with_timezone(tz).format(DATETIME_FORMAT).to_string()
+}
+
+/// How a guild wants dates rendered: Discord timestamp markup, shown live in each reader's zone, or plain text.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum TimestampStyle {
+    Discord,
+    Plain,
+}
+
+impl FromStr for TimestampStyle {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "discord" => Ok(TimestampStyle::Discord),
+            "plain" | "text" => Ok(TimestampStyle::Plain),
+            _ => Err(format!("Could not convert '{}' into a timestamp style (discord, plain)", s))
+        }
     }
 }
 
-pub fn get_next_day(now: DateTime<Utc>, reference_hour: u32) -> Date<Utc> {
-    if now.hour() < reference_hour {
-        now.date()
-    } else {
-        now.add(Duration::days(1)).date()
+impl Display for TimestampStyle {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            TimestampStyle::Discord => write!(f, "discord"),
+            TimestampStyle::Plain => write!(f, "plain"),
+        }
     }
 }
 
-pub fn get_daily_start(date: Date<Utc>) -> DateTime<Utc> {
-    date.and_hms(16, 0, 0)
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct TimeFormatter {
+    pub timezone: Tz,
+    pub style: TimestampStyle,
 }
 
-pub fn get_weekly_start(date: Date<Utc>) -> DateTime<Utc> {
-    date.and_hms(15, 0, 0)
+impl TimeFormatter {
+    pub fn new(timezone: Tz, style: TimestampStyle) -> Self {
+        Self { timezone, style }
+    }
+
+    /// `<t:unix:F>`, or the date in the configured zone.
+    pub fn datetime(&self, at: &DateTime<Utc>) -> String {
+        match self.style {
+            TimestampStyle::Discord => format!("<t:{}:F>", at.timestamp()),
+            TimestampStyle::Plain => format_datetime(at, &self.timezone),
+        }
+    }
+
+    /// `<t:unix:R>`, or the time left as `D days, HH:MM:SS` when computed from `now`.
+    pub fn countdown(&self, target: &DateTime<Utc>, now: &DateTime<Utc>, days_label: &str) -> String {
+        match self.style {
+            TimestampStyle::Discord => format!("<t:{}:R>", target.timestamp()),
+            TimestampStyle::Plain => {
+                let (days_left, hours_left, mins_left, secs_left) = get_time_left(*target, *now);
+                if days_left > 0 {
+                    format!("{} {}, {:0>2}:{:0>2}:{:0>2}", days_left, days_label, hours_left, mins_left, secs_left)
+                } else {
+                    format!("{:0>2}:{:0>2}:{:0>2}", hours_left, mins_left, secs_left)
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::{TimeZone, Utc};
+    use chrono_tz::Tz;
+
+    use crate::utils::time::{format_datetime, parse_timezone, TimeFormatter, TimestampStyle};
+
+    #[test]
+    pub fn parse_iana_and_offsets() {
+        assert_eq!(parse_timezone("Europe/Paris"), Ok(Tz::Europe__Paris));
+        assert_eq!(parse_timezone("+2"), Ok(Tz::Etc__GMTMinus2));
+        assert_eq!(parse_timezone("UTC-5"), Ok(Tz::Etc__GMTPlus5));
+        assert_eq!(parse_timezone("0"), Ok(Tz::Etc__GMT));
+        assert!(parse_timezone("Mars/Olympus").is_err());
+        assert!(parse_timezone("+42").is_err());
+    }
+
+    #[test]
+    pub fn format_follows_daylight_saving() {
+        let winter = Utc.with_ymd_and_hms(2020, 1, 6, 15, 0, 0).unwrap();
+        let summer = Utc.with_ymd_and_hms(2020, 7, 6, 15, 0, 0).unwrap();
+        assert_eq!(format_datetime(&winter, &Tz::Europe__Paris), "2020-01-06 16:00:00 CET");
+        assert_eq!(format_datetime(&summer, &Tz::Europe__Paris), "2020-07-06 17:00:00 CEST");
+    }
+
+    #[test]
+    pub fn discord_and_plain_timestamps() {
+        let now = Utc.with_ymd_and_hms(2020, 9, 7, 12, 0, 0).unwrap();
+        let reset = Utc.with_ymd_and_hms(2020, 9, 7, 16, 0, 0).unwrap();
+        let next_week = Utc.with_ymd_and_hms(2020, 9, 14, 15, 0, 0).unwrap();
+        let discord = TimeFormatter::new(Tz::Europe__Paris, TimestampStyle::Discord);
+        assert_eq!(discord.datetime(&reset), "<t:1599494400:F>");
+        assert_eq!(discord.countdown(&reset, &now, "days"), "<t:1599494400:R>");
+        let plain = TimeFormatter::new(Tz::Europe__Paris, TimestampStyle::Plain);
+        assert_eq!(plain.datetime(&reset), "2020-09-07 18:00:00 CEST");
+        assert_eq!(plain.countdown(&reset, &now, "days"), "04:00:00");
+        assert_eq!(plain.countdown(&next_week, &now, "days"), "7 days, 03:00:00");
+        assert_eq!("Plain".parse::<TimestampStyle>(), Ok(TimestampStyle::Plain));
+        assert_eq!(TimestampStyle::Discord.to_string().parse::<TimestampStyle>(), Ok(TimestampStyle::Discord));
+    }
 }